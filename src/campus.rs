@@ -0,0 +1,228 @@
+use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime};
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::io;
+use std::sync::{Arc, Mutex};
+
+use crate::catalogue::Catalogue;
+use crate::filter::FilterSet;
+use crate::free::{self, Booking};
+use crate::holidays::Closures;
+use crate::hours::OpeningHours;
+use crate::index::RoomIndex;
+use crate::loadingbar::Loadingbar;
+use crate::locale::Msg;
+use crate::ranking::{Ranking, RankingContext};
+use crate::room::Location;
+use crate::timetable::{self, TimetableDay};
+use crate::topology::{GroupDistances, Origins, Topology};
+use crate::{partial, quiet, FreeRoom};
+
+/// Availability of a room at a single point in time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Availability {
+    Free,
+    Busy,
+    Closed,
+}
+
+/// All room data loaded once from the local files: the room index with the bookings of every room,
+/// the catalogue, the topology, the opening hours and the closure days.
+/// Used to answer many queries without reading the calendars again, e.g. in the terminal UI.
+pub struct Campus {
+    pub index: RoomIndex,
+    pub catalogue: Catalogue,
+    pub topology: Option<Topology>,
+    pub opening_hours: OpeningHours,
+    pub closures: Closures,
+    bookings: HashMap<Location, Vec<Booking>>,
+}
+
+impl Campus {
+    /// Loads the room index and the bookings of all rooms with a loading bar.
+    /// Never downloads anything, returns an error if there are no room calendars yet.
+    pub fn load() -> Result<Self, Box<dyn std::error::Error>> {
+        let index = RoomIndex::load()
+            .map_err(|e| io::Error::new(e.kind(), Msg::NoRoomData(&e).to_string()))?;
+        let rooms: Vec<&Location> = index.locations().collect();
+        let bar = Arc::new(Mutex::new(Loadingbar::new(
+            &Msg::LoadingRooms.to_string(),
+            rooms.len(),
+        )));
+        let bookings = rooms
+            .par_iter()
+            .map(|location| {
+                let bookings = index.bookings(location)?;
+                bar.lock().unwrap().next();
+                Ok(((*location).clone(), bookings))
+            })
+            .collect::<io::Result<_>>()?;
+        bar.lock().unwrap().finish();
+        Ok(Campus {
+            catalogue: Catalogue::load()?,
+            topology: Topology::load()?,
+            opening_hours: OpeningHours::load()?,
+            closures: Closures::load()?,
+            index,
+            bookings,
+        })
+    }
+
+    /// Returns whether a room is free, booked or closed (campus closed or outside of the
+    /// opening hours of its block) at the given time.
+    pub fn availability(&self, location: &Location, at: NaiveDateTime) -> Availability {
+        let open = self.closures.closed_reason(at.date()).is_none()
+            && self
+                .opening_hours
+                .hours_on(location.block(), at.date())
+                .is_some_and(|hours| hours.open <= at.time() && at.time() < hours.close);
+        if !open {
+            return Availability::Closed;
+        }
+        let busy = self
+            .bookings
+            .get(location)
+            .is_some_and(|bookings| bookings.iter().any(|b| b.start <= at && at < b.end));
+        if busy {
+            Availability::Busy
+        } else {
+            Availability::Free
+        }
+    }
+
+    /// Returns the closed days (public holidays and closure days) of the window as bookings
+    /// over the whole day, so they count as taken in every room.
+    /// A window ending at midnight does not include the following day.
+    pub fn closed_bookings(
+        &self,
+        start_time: NaiveDateTime,
+        enddatetime: NaiveDateTime,
+    ) -> Vec<Booking> {
+        let last_day = (enddatetime - Duration::seconds(1)).date();
+        self.closures
+            .closed_days(start_time.date(), last_day)
+            .into_iter()
+            .map(|(date, _)| Booking {
+                start: date.and_time(NaiveTime::MIN),
+                end: (date + Duration::days(1)).and_time(NaiveTime::MIN),
+            })
+            .collect()
+    }
+
+    /// Returns the bookings of a room, none for unknown rooms.
+    pub fn bookings(&self, location: &Location) -> &[Booking] {
+        self.bookings.get(location).map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns the rooms a search looks at and how to get their distances from the origins.
+    /// These are the rooms that pass the include and exclude lists and meet the requirements
+    /// on their catalogue metadata. If accessible rooms are required, distances follow step-free
+    /// routes only and rooms that cannot be reached without stairs are left out.
+    pub fn candidates(
+        &self,
+        origins: &Origins,
+        filter: &FilterSet,
+    ) -> (Vec<Location>, GroupDistances<'_>) {
+        let step_free = filter.requirements.accessible;
+        let distances = origins.distances(self.topology.as_ref(), step_free);
+        let rooms = self
+            .index
+            .locations()
+            .filter(|location| filter.check(location, self.catalogue.get(location)).is_ok())
+            .filter(|location| !step_free || distances.to(location).0 != u32::MAX)
+            .cloned()
+            .collect();
+        (rooms, distances)
+    }
+
+    /// Finds the free rooms in the window and ranks them, see `get_rooms`.
+    /// The window is trimmed to the opening hours of each room's block,
+    /// no room is free if the campus is closed on a day of the window.
+    pub fn free_rooms(
+        &self,
+        origins: &Origins,
+        start_time: NaiveDateTime,
+        enddatetime: NaiveDateTime,
+        filter: &FilterSet,
+        ranking: &Ranking,
+    ) -> Vec<FreeRoom> {
+        let (paths, distances) = self.candidates(origins, filter);
+
+        // Bookings of all rooms are needed, filtered out rooms can still be noisy neighbours
+        let rooms: Vec<Location> = self.index.locations().cloned().collect();
+        let occupancy: HashMap<Location, f64> = self
+            .bookings
+            .iter()
+            .map(|(location, bookings)| {
+                let taken = free::taken_intervals(bookings, start_time, enddatetime);
+                let busy = 1.0 - partial::free_fraction(&taken, start_time, enddatetime);
+                (location.clone(), busy)
+            })
+            .collect();
+
+        let closed = self.closed_bookings(start_time, enddatetime);
+        let mut min_keys: Vec<FreeRoom> = Vec::new();
+        for location in &paths {
+            let block = location.block();
+            let bookings = self.bookings(location);
+            let free_window = self
+                .opening_hours
+                .trim(block, start_time, enddatetime)
+                .ok()
+                .filter(|(start, end)| {
+                    free::is_free_in(bookings, *start, *end)
+                        && free::is_free_in(&closed, *start, *end)
+                });
+            let Some((start, end)) = free_window else {
+                continue;
+            };
+            let quietness = quiet::quietness(location, &rooms, &occupancy, self.topology.as_ref());
+            if filter
+                .requirements
+                .min_quietness
+                .is_some_and(|min| quietness < min)
+            {
+                continue;
+            }
+            let closing = start.date().and_time(
+                self.opening_hours
+                    .hours_on(block, start.date())
+                    .map(|hours| hours.close)
+                    .unwrap_or(end.time()),
+            );
+            let next_event = free::next_booking(bookings, start);
+            let free_until = next_event.map_or(closing, |next| next.min(closing));
+            let (distance, each) = distances.to(location);
+            min_keys.push(FreeRoom {
+                room: location.clone(),
+                distance,
+                distances: each,
+                free_until,
+                next_event,
+                quietness,
+                score: 0.0,
+                reasons: Vec::new(),
+            });
+        }
+        ranking.rank(
+            &mut min_keys,
+            &RankingContext {
+                origins: &origins.rooms,
+                start: start_time,
+                catalogue: &self.catalogue,
+            },
+        );
+        min_keys
+    }
+
+    /// Builds the timetable of a room on one day, see `get_timetable`.
+    pub fn timetable(&self, location: &Location, date: NaiveDate) -> io::Result<TimetableDay> {
+        let events = self.index.events(location)?;
+        Ok(timetable::day(
+            &events,
+            date,
+            self.opening_hours.hours_on(location.block(), date),
+            self.closures.closed_reason(date),
+        ))
+    }
+}
@@ -0,0 +1,157 @@
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fmt, fs, io, path::Path, str::FromStr};
+
+use crate::locale::Msg;
+use crate::room::Location;
+
+const CATALOGUE_TOML: &str = "catalogue.toml";
+const CATALOGUE_JSON: &str = "catalogue.json";
+
+/// Kind of room as listed in the room catalogue.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RoomType {
+    LectureHall,
+    Lab,
+    PcPool,
+    SeminarRoom,
+    Other,
+}
+
+impl FromStr for RoomType {
+    type Err = String;
+
+    /// Parses a room type from its catalogue name (e.g. "pc_pool") or a German name ("Hörsaal").
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().replace('-', "_").as_str() {
+            "lecture_hall" | "hörsaal" | "hoersaal" => Ok(RoomType::LectureHall),
+            "lab" | "labor" => Ok(RoomType::Lab),
+            "pc_pool" | "pc" => Ok(RoomType::PcPool),
+            "seminar_room" | "seminarraum" => Ok(RoomType::SeminarRoom),
+            "other" | "sonstige" => Ok(RoomType::Other),
+            _ => Err(Msg::InvalidRoomType(&s).to_string()),
+        }
+    }
+}
+
+impl fmt::Display for RoomType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            RoomType::LectureHall => "lecture_hall",
+            RoomType::Lab => "lab",
+            RoomType::PcPool => "pc_pool",
+            RoomType::SeminarRoom => "seminar_room",
+            RoomType::Other => "other",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Metadata of a single room: seats, kind of room, equipment and wheelchair accessibility.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct RoomInfo {
+    pub capacity: Option<u32>,
+    #[serde(rename = "type")]
+    pub room_type: Option<RoomType>,
+    #[serde(default)]
+    pub equipment: Vec<String>,
+    #[serde(default)]
+    pub accessible: bool,
+}
+
+/// Room catalogue loaded from catalogue.toml or catalogue.json, keyed by room name.
+/// Room ids are normalized on load, so "a 2.66" and "A266" refer to the same room.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Catalogue {
+    #[serde(default)]
+    pub rooms: BTreeMap<String, RoomInfo>,
+}
+
+/// Normalizes a catalogue key to the canonical room name in upper case.
+fn normalize(name: &str) -> String {
+    Location::parse(name)
+        .map_or(name.to_owned(), |location| location.to_string())
+        .to_uppercase()
+}
+
+impl Catalogue {
+    /// Loads the room catalogue from catalogue.toml, or from catalogue.json if there is no TOML file.
+    /// Returns an empty catalogue if neither file exists.
+    pub fn load() -> io::Result<Self> {
+        let mut catalogue: Catalogue = if Path::new(CATALOGUE_TOML).exists() {
+            let text = fs::read_to_string(CATALOGUE_TOML)?;
+            toml::from_str(&text).map_err(io::Error::other)?
+        } else if Path::new(CATALOGUE_JSON).exists() {
+            let text = fs::read_to_string(CATALOGUE_JSON)?;
+            serde_json::from_str(&text).map_err(io::Error::other)?
+        } else {
+            Catalogue::default()
+        };
+        catalogue.rooms = catalogue
+            .rooms
+            .into_iter()
+            .map(|(name, info)| (normalize(&name), info))
+            .collect();
+        Ok(catalogue)
+    }
+
+    /// Returns the metadata of a room, if it is listed in the catalogue.
+    pub fn get(&self, location: &Location) -> Option<&RoomInfo> {
+        self.rooms.get(&location.to_string().to_uppercase())
+    }
+}
+
+/// Requirements a room has to meet, e.g. for a meeting of 12 people with a projector.
+/// Rooms without catalogue entry only meet empty requirements.
+/// `accessible` also makes the search use step-free routes only.
+/// `min_quietness` (0.0 to 1.0) depends on the search window and is checked by the search,
+/// not against the catalogue.
+#[derive(Debug, Clone, Default)]
+pub struct Requirements {
+    pub min_capacity: Option<u32>,
+    pub equipment: Vec<String>,
+    pub room_type: Option<RoomType>,
+    pub accessible: bool,
+    pub min_quietness: Option<f64>,
+}
+
+impl Requirements {
+    /// Checks the metadata of a room against the requirements.
+    /// Returns the reason if the room does not meet them.
+    pub fn check(&self, info: Option<&RoomInfo>) -> Result<(), String> {
+        if self.min_capacity.is_none()
+            && self.equipment.is_empty()
+            && self.room_type.is_none()
+            && !self.accessible
+        {
+            return Ok(());
+        }
+        let Some(info) = info else {
+            return Err(Msg::NotInCatalogue.to_string());
+        };
+        if let Some(min_capacity) = self.min_capacity {
+            match info.capacity {
+                Some(capacity) if capacity >= min_capacity => {}
+                Some(capacity) => return Err(Msg::OnlySeats(&capacity).to_string()),
+                None => return Err(Msg::UnknownCapacity.to_string()),
+            }
+        }
+        if let Some(missing) = self.equipment.iter().find(|wanted| {
+            !info
+                .equipment
+                .iter()
+                .any(|equipment| equipment.eq_ignore_ascii_case(wanted))
+        }) {
+            return Err(Msg::Missing(missing).to_string());
+        }
+        if let Some(room_type) = self.room_type {
+            if info.room_type != Some(room_type) {
+                return Err(Msg::NotOfType(room_type).to_string());
+            }
+        }
+        if self.accessible && !info.accessible {
+            return Err(Msg::NotAccessible.to_string());
+        }
+        Ok(())
+    }
+}
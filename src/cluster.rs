@@ -0,0 +1,178 @@
+use std::collections::{BTreeMap, HashSet};
+
+use crate::room::{Location, RoomId};
+use crate::topology::{Distances, Topology};
+
+/// Size of the wanted clusters and how far apart the rooms of a cluster may be,
+/// in the same unit as the distances from the origin room.
+#[derive(Debug, Clone, Copy)]
+pub struct ClusterQuery {
+    pub size: usize,
+    pub max_gap: u32,
+}
+
+/// A group of free rooms next to each other on the same floor of a block.
+/// `distance` is the distance from the origin room to the farthest room of the cluster,
+/// `span` the largest distance between two rooms of the cluster.
+#[derive(Debug, Clone)]
+pub struct Cluster {
+    pub rooms: Vec<RoomId>,
+    pub distance: u32,
+    pub span: u32,
+}
+
+/// Finds clusters of `query.size` free rooms on the same floor of a block
+/// whose pairwise distance is at most `query.max_gap`.
+/// - `free`: The free rooms with their distance from the origin room.
+/// - `topology`: The campus topology the distances between the rooms are measured on, like
+///   the distances from the origin room, or None to use the simple formula.
+/// - `step_free`: If true, only routes without stairs are used.
+///
+/// Returns clusters that do not share rooms, sorted by distance, then by span.
+pub fn find_clusters(
+    free: &[(RoomId, u32)],
+    query: &ClusterQuery,
+    topology: Option<&Topology>,
+    step_free: bool,
+) -> Vec<Cluster> {
+    if query.size == 0 {
+        return Vec::new();
+    }
+    let mut floors: BTreeMap<(char, i8), Vec<(RoomId, u32)>> = BTreeMap::new();
+    for (room, distance) in free {
+        floors
+            .entry((room.block, room.floor))
+            .or_default()
+            .push((*room, *distance));
+    }
+
+    let mut candidates: Vec<Cluster> = Vec::new();
+    for rooms in floors.values_mut() {
+        rooms.sort();
+        // Rooms are sorted by number, so the first and last room of a window are the farthest apart
+        for window in rooms.windows(query.size) {
+            let (first, _) = window[0];
+            let (last, _) = window[window.len() - 1];
+            let span = Distances::new(topology, &first, step_free).to(&Location::Room(last));
+            if span <= query.max_gap {
+                candidates.push(Cluster {
+                    rooms: window.iter().map(|(room, _)| *room).collect(),
+                    distance: window
+                        .iter()
+                        .map(|(_, distance)| *distance)
+                        .max()
+                        .unwrap_or(0),
+                    span,
+                });
+            }
+        }
+    }
+    candidates.sort_by_key(|cluster| (cluster.distance, cluster.span));
+
+    let mut used: HashSet<RoomId> = HashSet::new();
+    candidates
+        .into_iter()
+        .filter(|cluster| {
+            if cluster.rooms.iter().any(|room| used.contains(room)) {
+                return false;
+            }
+            used.extend(cluster.rooms.iter().copied());
+            true
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::topology::{Block, Connection, ConnectionKind};
+
+    fn room(name: &str) -> RoomId {
+        name.parse().unwrap()
+    }
+
+    fn free(rooms: &[(&str, u32)]) -> Vec<(RoomId, u32)> {
+        rooms
+            .iter()
+            .map(|(name, distance)| (room(name), *distance))
+            .collect()
+    }
+
+    fn query(size: usize, max_gap: u32) -> ClusterQuery {
+        ClusterQuery { size, max_gap }
+    }
+
+    fn names(clusters: &[Cluster]) -> Vec<Vec<String>> {
+        clusters
+            .iter()
+            .map(|cluster| cluster.rooms.iter().map(RoomId::to_string).collect())
+            .collect()
+    }
+
+    #[test]
+    fn finds_contiguous_rooms_on_one_floor() {
+        let rooms = free(&[
+            ("A201", 5),
+            ("A202", 4),
+            ("A203", 3),
+            ("A301", 1),
+            ("B202", 1),
+        ]);
+        let clusters = find_clusters(&rooms, &query(3, 2), None, false);
+        assert_eq!(names(&clusters), [["A201", "A202", "A203"]]);
+        assert_eq!((clusters[0].distance, clusters[0].span), (5, 2));
+    }
+
+    #[test]
+    fn taken_or_closed_rooms_split_clusters() {
+        // A203 is not free, so A202 and A204 are two numbers apart
+        let rooms = free(&[("A201", 1), ("A202", 2), ("A204", 4), ("A205", 5)]);
+        assert_eq!(
+            names(&find_clusters(&rooms, &query(2, 1), None, false)),
+            [["A201", "A202"], ["A204", "A205"]]
+        );
+        assert!(find_clusters(&rooms, &query(3, 2), None, false).is_empty());
+        assert_eq!(
+            names(&find_clusters(&rooms, &query(3, 3), None, false)),
+            [["A201", "A202", "A204"]]
+        );
+    }
+
+    #[test]
+    fn clusters_do_not_share_rooms() {
+        let rooms = free(&[("A201", 3), ("A202", 2), ("A203", 1)]);
+        let clusters = find_clusters(&rooms, &query(2, 1), None, false);
+        assert_eq!(names(&clusters), [["A202", "A203"]]);
+        assert!(find_clusters(&rooms, &query(0, 1), None, false).is_empty());
+        assert!(find_clusters(&rooms, &query(4, 10), None, false).is_empty());
+    }
+
+    #[test]
+    fn gaps_are_walking_times_with_a_topology() {
+        let topology = Topology {
+            room_step: 2,
+            blocks: [(
+                'B',
+                Block {
+                    room_step: Some(30),
+                },
+            )]
+            .into(),
+            connections: vec![Connection {
+                kind: ConnectionKind::Stairs,
+                from: "A2.01".to_owned(),
+                to: "A3.01".to_owned(),
+                seconds: 20,
+            }],
+            neighbours: Default::default(),
+        };
+        let rooms = free(&[("A201", 10), ("A203", 14), ("B201", 100), ("B203", 160)]);
+        // Two room numbers apart are 4 seconds in block A and 60 seconds in block B
+        let clusters = find_clusters(&rooms, &query(2, 10), Some(&topology), false);
+        assert_eq!(names(&clusters), [["A201", "A203"]]);
+        assert_eq!(clusters[0].span, 4);
+        let clusters = find_clusters(&rooms, &query(2, 60), Some(&topology), false);
+        assert_eq!(clusters.len(), 2);
+        assert_eq!(clusters[1].span, 60);
+    }
+}
@@ -1,21 +1,62 @@
 use chrono::{DateTime, Utc};
+use dhbw_roomfinder::filter::{FilterSet, RoomFilter};
+use dhbw_roomfinder::ranking::strategy;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use std::{
+    collections::BTreeMap,
     fs::File,
     io::{self, Read, Write},
     path::Path,
 };
 
-use crate::room::RoomId;
+use dhbw_roomfinder::locale::{Lang, Msg};
+use dhbw_roomfinder::room::{RoomId, RoomIdError};
+use dhbw_roomfinder::topology::{Aggregate, Origins};
 
 const CONFIG_FILE: &str = "config.json";
 
-/// Configuration struct holds the selected room and last updated time.
+/// Configuration struct holds the selected room, last updated time,
+/// the user's room include/exclude lists, the teams with their own lists,
+/// the weights of the ranking strategies, the number of results per page and the language.
 /// Provides methods to load and save configuration from a JSON file.
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Config {
     pub room: RoomId,
     pub last_updated: DateTime<Utc>,
+    #[serde(default)]
+    pub filter: RoomFilter,
+    #[serde(default)]
+    pub teams: BTreeMap<String, Team>,
+    #[serde(default)]
+    pub ranking: BTreeMap<String, f64>,
+    #[serde(default)]
+    pub count: Option<usize>,
+    #[serde(default)]
+    pub lang: Option<Lang>,
+}
+
+/// Settings shared by a team, e.g. rooms the whole team never wants to use,
+/// the rooms the members sit in and how their distances are combined.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct Team {
+    #[serde(default)]
+    pub filter: RoomFilter,
+    #[serde(default)]
+    pub rooms: Vec<RoomId>,
+    #[serde(default)]
+    pub aggregate: Aggregate,
+}
+
+/// Converts a room name (e.g. "A266") into a RoomId value, other values are kept.
+fn room_value(value: Value) -> Result<Value, String> {
+    match value {
+        Value::String(name) => {
+            let room: RoomId = name.parse().map_err(|e: RoomIdError| e.to_string())?;
+            serde_json::to_value(room).map_err(|e| e.to_string())
+        }
+        value => Ok(value),
+    }
 }
 
 impl Config {
@@ -27,27 +68,37 @@ impl Config {
             let mut file = File::open(CONFIG_FILE)?;
             let mut json = String::new();
             file.read_to_string(&mut json)?;
-            let mut config: Config =
-                serde_json::from_str(&json).map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+            let mut config: Config = serde_json::from_str(&json).map_err(io::Error::other)?;
             if let Some(roomname) = room {
-                config.room = RoomId::from_str(&roomname).expect("failed to parse roomname");
+                config.room = roomname.parse().map_err(io::Error::other)?;
                 let _ = config.save();
             }
             Ok(config)
         } else {
             if let Some(roomname) = room {
-                let _ = (&Config {
+                let _ = Config {
                     room: RoomId {
                         block: 'A',
                         floor: 2,
                         number: 66,
+                        suffix: None,
                     },
                     last_updated: Utc::now(),
-                })
-                    .save();
+                    filter: RoomFilter::default(),
+                    teams: BTreeMap::new(),
+                    ranking: BTreeMap::new(),
+                    count: None,
+                    lang: None,
+                }
+                .save();
                 Ok(Config {
-                    room: RoomId::from_str(&roomname).expect("failed to parse roomname"),
+                    room: roomname.parse().map_err(io::Error::other)?,
                     last_updated: Utc::now(),
+                    filter: RoomFilter::default(),
+                    teams: BTreeMap::new(),
+                    ranking: BTreeMap::new(),
+                    count: None,
+                    lang: None,
                 })
             } else {
                 Ok(Config {
@@ -55,18 +106,196 @@ impl Config {
                         block: ('C'),
                         floor: (0),
                         number: (0),
+                        suffix: None,
                     },
                     last_updated: Utc::now(),
+                    filter: RoomFilter::default(),
+                    teams: BTreeMap::new(),
+                    ranking: BTreeMap::new(),
+                    count: None,
+                    lang: None,
                 })
             }
         }
     }
+    /// Collects the room filters of the user and, if given, of the named team.
+    /// Returns an error if the team is not configured.
+    pub fn filters(&self, team: Option<&str>) -> Result<FilterSet, String> {
+        let mut filters = vec![(Msg::UserFilter.to_string(), self.filter.clone())];
+        if let Some(name) = team {
+            let team = self
+                .teams
+                .get(name)
+                .ok_or_else(|| Msg::UnknownTeam(&name).to_string())?;
+            filters.push((Msg::TeamFilter(&name).to_string(), team.filter.clone()));
+        }
+        Ok(FilterSet {
+            filters,
+            ..FilterSet::default()
+        })
+    }
+    /// Returns the rooms of the named team's members if the team has any,
+    /// otherwise the configured room.
+    pub fn origins(&self, team: Option<&str>) -> Origins {
+        match team.and_then(|name| self.teams.get(name)) {
+            Some(team) if !team.rooms.is_empty() => Origins {
+                rooms: team.rooms.clone(),
+                aggregate: team.aggregate,
+            },
+            _ => Origins::single(self.room),
+        }
+    }
+    /// Returns the value at a dotted key (e.g. "room" or "filter.exclude") as JSON,
+    /// or the whole configuration if no key is given.
+    pub fn get(&self, key: Option<&str>) -> Result<Value, String> {
+        let mut value = serde_json::to_value(self).map_err(|e| e.to_string())?;
+        for part in key.into_iter().flat_map(|key| key.split('.')) {
+            value = value
+                .get_mut(part)
+                .map(Value::take)
+                .ok_or_else(|| Msg::UnknownKey(&key.unwrap_or_default()).to_string())?;
+        }
+        Ok(value)
+    }
+
+    /// Sets the value at a dotted key (e.g. "ranking.nearest" or "teams.lerngruppe.aggregate").
+    /// The value is read as JSON and taken as a plain string if that fails,
+    /// rooms can also be given by name (e.g. "A266" or `["A266", "B301"]` for team rooms).
+    /// Missing objects on the way are created. Returns an error if the key is unknown
+    /// or the result is not a valid configuration.
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        let mut new_value: Value =
+            serde_json::from_str(value).unwrap_or_else(|_| Value::String(value.to_owned()));
+        match key.split('.').collect::<Vec<_>>().as_slice() {
+            ["room"] => new_value = room_value(new_value)?,
+            ["teams", _, "rooms"] => {
+                if let Value::Array(rooms) = new_value {
+                    new_value = Value::Array(
+                        rooms
+                            .into_iter()
+                            .map(room_value)
+                            .collect::<Result<_, _>>()?,
+                    );
+                }
+            }
+            _ => {}
+        }
+
+        let mut config = serde_json::to_value(&*self).map_err(|e| e.to_string())?;
+        let mut target = &mut config;
+        for part in key.split('.') {
+            let Value::Object(map) = target else {
+                return Err(Msg::UnknownKey(&key).to_string());
+            };
+            target = map
+                .entry(part)
+                .or_insert_with(|| Value::Object(Default::default()));
+        }
+        *target = new_value;
+        let config: Config =
+            serde_json::from_value(config).map_err(|e| Msg::InvalidValue(&key, &e).to_string())?;
+        for name in config.ranking.keys() {
+            strategy(name)?;
+        }
+        if config.count == Some(0) {
+            return Err(Msg::CountAtLeastOne.to_string());
+        }
+        // Keys the configuration does not know are dropped when it is read back
+        let saved = serde_json::to_value(&config).map_err(|e| e.to_string())?;
+        if key
+            .split('.')
+            .try_fold(&saved, |value, part| value.get(part))
+            .is_none()
+        {
+            return Err(Msg::UnknownKey(&key).to_string());
+        }
+        *self = config;
+        Ok(())
+    }
     /// Saves configuration struct to config.json in pretty JSON format.
     pub fn save(&self) -> io::Result<()> {
-        let json_string = serde_json::to_string_pretty(&self)
-            .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+        let json_string = serde_json::to_string_pretty(&self).map_err(io::Error::other)?;
         let mut file = File::create(CONFIG_FILE)?;
         file.write_all(json_string.as_bytes())?;
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn config() -> Config {
+        Config {
+            room: "A266".parse().unwrap(),
+            last_updated: Utc::now(),
+            filter: RoomFilter::default(),
+            teams: BTreeMap::new(),
+            ranking: BTreeMap::new(),
+            count: None,
+            lang: None,
+        }
+    }
+
+    #[test]
+    fn sets_values_at_dotted_keys() {
+        let mut config = config();
+        config.set("ranking.nearest", "2").unwrap();
+        config.set("filter.exclude", r#"["B4*"]"#).unwrap();
+        config.set("teams.lerngruppe.aggregate", "max").unwrap();
+        config
+            .set("teams.lerngruppe.rooms", r#"["A266", "B301"]"#)
+            .unwrap();
+        config.set("room", "B301").unwrap();
+        assert_eq!(config.ranking["nearest"], 2.0);
+        assert_eq!(config.get(Some("filter.exclude")), Ok(json!(["B4*"])));
+        let team = &config.teams["lerngruppe"];
+        assert_eq!(team.aggregate, Aggregate::Max);
+        assert_eq!(
+            team.rooms,
+            ["A266".parse().unwrap(), "B301".parse().unwrap()]
+        );
+        assert_eq!(config.room, "B301".parse().unwrap());
+        assert_eq!(config.get(Some("room.floor")), Ok(json!(3)));
+    }
+
+    #[test]
+    fn unsets_values_with_null() {
+        let mut config = config();
+        config.set("lang", "de").unwrap();
+        config.set("count", "20").unwrap();
+        assert_eq!(config.lang, Some(Lang::De));
+        assert_eq!(config.count, Some(20));
+        config.set("lang", "null").unwrap();
+        config.set("count", "null").unwrap();
+        assert_eq!(config.lang, None);
+        assert_eq!(config.count, None);
+    }
+
+    #[test]
+    fn rejects_unknown_keys_and_wrong_types() {
+        let mut config = config();
+        config.set("count", "5").unwrap();
+        for (key, value) in [
+            ("unknown", "1"),
+            ("filter.unknown", "1"),
+            ("room.floor.number", "1"),
+            ("count", "zehn"),
+            ("count", "0"),
+            ("count", "-1"),
+            ("filter", "5"),
+            ("lang", "fr"),
+            ("ranking.fastest", "1"),
+            ("ranking.nearest", "viel"),
+            ("room", "Z"),
+            ("teams.lerngruppe.rooms", r#"["A266", "Z"]"#),
+        ] {
+            assert!(config.set(key, value).is_err(), "{} {}", key, value);
+        }
+        // Nothing is changed by a failed call
+        assert_eq!(config.count, Some(5));
+        assert!(config.teams.is_empty());
+        assert!(config.get(Some("unknown")).is_err());
+    }
+}
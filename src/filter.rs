@@ -0,0 +1,126 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::catalogue::{Requirements, RoomInfo};
+use crate::locale::Msg;
+use crate::room::Location;
+
+/// Include and exclude lists for rooms.
+/// Each entry is a room id ("A266"), a block ("B") or a glob pattern ("B4*", "A2??").
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct RoomFilter {
+    #[serde(default)]
+    pub include: Vec<String>,
+    #[serde(default)]
+    pub exclude: Vec<String>,
+}
+
+/// Room filters from several sources (e.g. the user and a team), named for explanations,
+/// plus the requirements on the room's catalogue metadata.
+/// A room is filtered out if it matches any exclude pattern, if include patterns
+/// exist and it matches none of them, or if it does not meet the requirements.
+#[derive(Debug, Clone, Default)]
+pub struct FilterSet {
+    pub filters: Vec<(String, RoomFilter)>,
+    pub requirements: Requirements,
+}
+
+/// Checks whether a location matches a room id, block or glob pattern (case-insensitive).
+/// Room ids may be written in any form the RoomId parser accepts ("A266", "a 266", "A2.66").
+pub fn matches(pattern: &str, location: &Location) -> bool {
+    let mut chars = pattern.chars();
+    if let (Some(block), None) = (chars.next(), chars.next()) {
+        if block.is_ascii_alphabetic() {
+            return location.block() == Some(block.to_ascii_uppercase());
+        }
+    }
+    if !pattern.contains(['*', '?']) {
+        return match (Location::parse(pattern), location) {
+            (Some(Location::Room(room)), Location::Room(other)) => room == *other,
+            (Some(Location::Named(name)), Location::Named(other)) => {
+                name.to_lowercase() == other.to_lowercase()
+            }
+            _ => false,
+        };
+    }
+    let glob = regex::escape(pattern)
+        .replace("\\*", ".*")
+        .replace("\\?", ".");
+    Regex::new(&format!("(?i)^{}$", glob))
+        .map(|re| re.is_match(&location.to_string()))
+        .unwrap_or(false)
+}
+
+impl FilterSet {
+    /// Checks a room and its catalogue metadata against all filters.
+    /// Returns the reason if the room is filtered out.
+    pub fn check(&self, location: &Location, info: Option<&RoomInfo>) -> Result<(), String> {
+        for (source, filter) in &self.filters {
+            if let Some(pattern) = filter
+                .exclude
+                .iter()
+                .find(|pattern| matches(pattern, location))
+            {
+                return Err(Msg::ExcludedBy(pattern, source).to_string());
+            }
+        }
+        let mut includes = self
+            .filters
+            .iter()
+            .flat_map(|(_, filter)| filter.include.iter())
+            .peekable();
+        if includes.peek().is_some() && !includes.any(|pattern| matches(pattern, location)) {
+            return Err(Msg::NotOnIncludeList.to_string());
+        }
+        self.requirements.check(info)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn location(name: &str) -> Location {
+        Location::parse(name).unwrap()
+    }
+
+    #[test]
+    fn matches_room_ids_in_any_spelling() {
+        assert!(matches("A267", &location("A267")));
+        assert!(matches("A2.67", &location("A267")));
+        assert!(matches("a 267", &location("A267")));
+        assert!(matches("A2166", &location("A2.166")));
+        assert!(matches("A2.166", &location("A2166")));
+        assert!(matches("A-1.05", &location("A-105")));
+        assert!(!matches("A2.67", &location("A266")));
+        assert!(!matches("A2166", &location("A216")));
+    }
+
+    #[test]
+    fn matches_blocks_globs_and_named_locations() {
+        assert!(matches("b", &location("B301")));
+        assert!(!matches("B", &location("A301")));
+        assert!(matches("A2*", &location("A266")));
+        assert!(matches("a2??", &location("A266")));
+        assert!(!matches("A2?", &location("A266")));
+        assert!(matches("audimax", &location("Audimax")));
+        assert!(!matches("Audimax", &location("A266")));
+        assert!(!matches("A266", &location("Audimax")));
+    }
+
+    #[test]
+    fn excludes_before_includes() {
+        let filter = RoomFilter {
+            include: vec!["A".to_owned()],
+            exclude: vec!["A2.67".to_owned(), "a 268".to_owned()],
+        };
+        let set = FilterSet {
+            filters: vec![("config".to_owned(), filter)],
+            requirements: Requirements::default(),
+        };
+        assert!(set.check(&location("A266"), None).is_ok());
+        assert!(set.check(&location("A267"), None).is_err());
+        assert!(set.check(&location("A268"), None).is_err());
+        assert!(set.check(&location("B266"), None).is_err());
+    }
+}
@@ -1,68 +1,170 @@
-use chrono::{Local, NaiveDateTime};
-use icalendar::{Calendar, CalendarComponent, CalendarDateTime, Component, DatePerhapsTime};
+use chrono::{Duration, NaiveDateTime, NaiveTime};
+use icalendar::{
+    Calendar, CalendarComponent, CalendarDateTime, Component, DatePerhapsTime, Event, EventStatus,
+};
 use rayon::prelude::*;
 
-use std::fs;
+use std::{fs, io};
 
-/// Prints the names of all events from a room's calendar that occur today.
-/// - `path`: The room identifier (filename in rooms/)
-#[allow(dead_code)]
-pub fn todays_events(path: &str) {
-    let content = fs::read_to_string(format!("rooms/{}.ics", path)).unwrap();
-    let calendar: Calendar = content.parse().unwrap();
+use crate::locale::Msg;
+use crate::timetable::{RoomEvent, Status};
 
-    let today = Local::now().date_naive();
+/// A single occupied interval taken from a room calendar.
+/// All-day events span the whole day of their start date.
+#[derive(Debug, Clone)]
+pub struct Booking {
+    pub start: NaiveDateTime,
+    pub end: NaiveDateTime,
+}
 
-    for component in &calendar.components {
-        if let CalendarComponent::Event(event) = component {
-            if let Some(dtstart) = event.get_start() {
-                if dtstart.date_naive() == today {
-                    println!("{}", event.get_summary().unwrap_or("").to_string());
-                }
-            }
+impl Booking {
+    /// Returns true if the booking touches the window between `start_time` and `enddatetime`.
+    pub fn overlaps(&self, start_time: NaiveDateTime, enddatetime: NaiveDateTime) -> bool {
+        (self.start >= start_time || self.end >= start_time)
+            && (self.start <= enddatetime || self.end <= enddatetime)
+            || (self.start <= start_time && start_time <= self.end)
+    }
+}
+
+/// Converts an iCalendar start or end value into a naive local datetime.
+/// Dates without a time are returned as `None`.
+fn naive_datetime(value: DatePerhapsTime) -> Option<NaiveDateTime> {
+    match value {
+        DatePerhapsTime::DateTime(dt) => Some(match dt {
+            CalendarDateTime::Utc(dt_utc) => dt_utc.naive_local(),
+            CalendarDateTime::Floating(naive_dt) => naive_dt,
+            CalendarDateTime::WithTimezone { date_time, .. } => date_time,
+        }),
+        DatePerhapsTime::Date(_) => None,
+    }
+}
+
+/// Returns the start and end of a calendar event.
+/// Events without a time span the whole day of their start date.
+fn interval(event: &Event) -> Option<(NaiveDateTime, NaiveDateTime)> {
+    let (dtstart, dtend) = (event.get_start()?, event.get_end()?);
+    match (naive_datetime(dtstart.clone()), naive_datetime(dtend)) {
+        (Some(start), Some(end)) => Some((start, end)),
+        _ => {
+            let date = dtstart.date_naive();
+            Some((
+                date.and_time(NaiveTime::MIN),
+                date.and_time(NaiveTime::MIN) + Duration::days(1) - Duration::seconds(1),
+            ))
         }
     }
 }
 
-/// Checks if a room is free for the given datetime.
-/// Returns true if no event occupies the room at that time,
-/// or if only all-day events are present on the same date.
+/// Reads and parses a room's calendar file.
+/// The error names the file, as it is usually read for many rooms at once.
+fn read_calendar(path: &str) -> io::Result<Calendar> {
+    let file = format!("rooms/{}.ics", path);
+    fs::read_to_string(&file)
+        .and_then(|content| content.parse().map_err(io::Error::other))
+        .map_err(|e| io::Error::new(e.kind(), Msg::ReadingFileFailed(&file, &e).to_string()))
+}
+
+/// Loads all bookings from a room's calendar.
 /// - `path`: The room identifier (filename in rooms/)
-/// - `datetime`: The local date and time to check
-pub fn is_free(path: &str, start_time: NaiveDateTime, enddatetime: NaiveDateTime) -> bool {
-    let content = fs::read_to_string(format!("rooms/{}.ics", path)).unwrap();
-    let calendar: Calendar = content.parse().unwrap();
+///
+/// Returns an error if the calendar file cannot be read or parsed.
+pub fn bookings(path: &str) -> io::Result<Vec<Booking>> {
+    let calendar = read_calendar(path)?;
 
-    let belegt = calendar.components.par_iter().any(|component| {
-        if let CalendarComponent::Event(event) = component {
-            if let (Some(dtstart), Some(dtend)) = (event.get_start(), event.get_end()) {
-                let start = match dtstart {
-                    DatePerhapsTime::DateTime(dt) => match dt {
-                        CalendarDateTime::Utc(dt_utc) => dt_utc.naive_local(),
-                        CalendarDateTime::Floating(naive_dt) => naive_dt,
-                        CalendarDateTime::WithTimezone { date_time, .. } => date_time,
-                    },
-                    DatePerhapsTime::Date(date) => return date == start_time.date(),
-                };
+    Ok(calendar
+        .components
+        .par_iter()
+        .filter_map(|component| match component {
+            CalendarComponent::Event(event) => {
+                interval(event).map(|(start, end)| Booking { start, end })
+            }
+            _ => None,
+        })
+        .collect())
+}
+
+/// Loads all events from a room's calendar with their title, courses and status.
+/// The title is the summary without the course list added when the room calendars are built.
+/// - `path`: The room identifier (filename in rooms/)
+pub fn events(path: &str) -> io::Result<Vec<RoomEvent>> {
+    let calendar = read_calendar(path)?;
 
-                let end = match dtend {
-                    DatePerhapsTime::DateTime(dt) => match dt {
-                        CalendarDateTime::Utc(dt_utc) => dt_utc.naive_local(),
-                        CalendarDateTime::Floating(naive_dt) => naive_dt,
-                        CalendarDateTime::WithTimezone { date_time, .. } => date_time,
-                    },
-                    DatePerhapsTime::Date(date) => {
-                        return date == start_time.date();
-                    }
-                };
+    Ok(calendar
+        .components
+        .iter()
+        .filter_map(|component| {
+            let CalendarComponent::Event(event) = component else {
+                return None;
+            };
+            let (start, end) = interval(event)?;
+            let kurs = event.property_value("X-KURS").unwrap_or("");
+            let summary = event.get_summary().unwrap_or("");
+            let title = summary
+                .strip_suffix(&format!(" ({})", kurs))
+                .unwrap_or(summary)
+                .to_owned();
+            let courses = kurs
+                .split(',')
+                .map(str::trim)
+                .filter(|course| !course.is_empty())
+                .map(str::to_owned)
+                .collect();
+            let status = event.get_status().map(|status| match status {
+                EventStatus::Tentative => Status::Tentative,
+                EventStatus::Confirmed => Status::Confirmed,
+                EventStatus::Cancelled => Status::Cancelled,
+            });
+            Some(RoomEvent {
+                start,
+                end,
+                title,
+                courses,
+                status,
+            })
+        })
+        .collect())
+}
 
-                return (start >= start_time || end >= start_time)
-                    && (start <= enddatetime || end <= enddatetime)
-                    || (start <= start_time && start_time <= end);
-            }
+/// Returns the parts of the window that are covered by bookings,
+/// clipped to the window, merged where they overlap and sorted by start.
+pub fn taken_intervals(
+    bookings: &[Booking],
+    start_time: NaiveDateTime,
+    enddatetime: NaiveDateTime,
+) -> Vec<(NaiveDateTime, NaiveDateTime)> {
+    let mut intervals: Vec<(NaiveDateTime, NaiveDateTime)> = bookings
+        .iter()
+        .map(|booking| (booking.start.max(start_time), booking.end.min(enddatetime)))
+        .filter(|(start, end)| start < end)
+        .collect();
+    intervals.sort();
+
+    let mut merged: Vec<(NaiveDateTime, NaiveDateTime)> = Vec::new();
+    for (start, end) in intervals {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
         }
-        false
-    });
+    }
+    merged
+}
+
+/// Returns the start of the first booking after the given time, if there is one.
+pub fn next_booking(bookings: &[Booking], after: NaiveDateTime) -> Option<NaiveDateTime> {
+    bookings
+        .iter()
+        .map(|booking| booking.start)
+        .filter(|start| *start > after)
+        .min()
+}
 
-    return !belegt;
+/// Checks if none of the given bookings touches the window.
+pub fn is_free_in(
+    bookings: &[Booking],
+    start_time: NaiveDateTime,
+    enddatetime: NaiveDateTime,
+) -> bool {
+    !bookings
+        .iter()
+        .any(|booking| booking.overlaps(start_time, enddatetime))
 }
@@ -0,0 +1,394 @@
+use clap::builder::StyledStr;
+use clap::error::{ContextKind, ContextValue, ErrorFormatter, ErrorKind};
+use clap::{Arg, Command, Parser};
+use dhbw_roomfinder::locale::{Lang, Msg};
+use std::ffi::OsString;
+
+/// Reads `--lang` from the raw command line, so the help and parser errors can
+/// already be shown in that language. Invalid values are left to the parser.
+pub fn lang_arg(args: &[OsString]) -> Option<Lang> {
+    let mut args = args.iter().map(|arg| arg.to_string_lossy());
+    while let Some(arg) = args.next() {
+        if arg == "--" {
+            break;
+        }
+        let value = match arg.strip_prefix("--lang") {
+            Some("") => args.next(),
+            Some(rest) if rest.starts_with('=') => Some(rest[1..].to_string().into()),
+            _ => continue,
+        };
+        return value.and_then(|value| value.parse().ok());
+    }
+    None
+}
+
+/// Parses the command line with help texts and errors in the selected language.
+/// Prints the help, the version or the error and exits if there is nothing to run.
+pub fn parse<T: Parser>(args: Vec<OsString>) -> T {
+    let mut cmd = localize(T::command());
+    cmd.try_get_matches_from_mut(args)
+        .and_then(|mut matches| {
+            T::from_arg_matches_mut(&mut matches).map_err(|e| e.format(&mut cmd))
+        })
+        .unwrap_or_else(|e| e.apply::<Localized>().exit())
+}
+
+/// Replaces the texts clap generates (about, argument help, headings,
+/// default and possible values) with the ones of the message catalogue.
+fn localize(mut cmd: Command) -> Command {
+    // Adds the help and version flags and the help subcommand, so they are translated too
+    cmd.build();
+    localize_command(cmd)
+}
+
+fn localize_command(mut cmd: Command) -> Command {
+    let name = cmd.get_name().to_string();
+    if let Some(about) = about(&name) {
+        cmd = cmd.about(about.to_string());
+    }
+    let usage = usage(&cmd.render_usage());
+    cmd = cmd
+        .help_template(format!(
+            "{{before-help}}{{about-with-newline}}\n{}\n\n{{all-args}}{{after-help}}",
+            usage
+        ))
+        .subcommand_help_heading(Msg::HelpCommands.to_string());
+    // Changing an argument moves it to the end, going through all of them keeps the order
+    let ids: Vec<String> = cmd
+        .get_arguments()
+        .map(|arg| arg.get_id().to_string())
+        .collect();
+    for id in ids {
+        cmd = cmd.mut_arg(&id, |arg| localize_arg(&name, arg));
+    }
+    let names: Vec<String> = cmd
+        .get_subcommands()
+        .map(|sub| sub.get_name().to_string())
+        .collect();
+    for name in names {
+        cmd = cmd.mut_subcommand(&name, localize_command);
+    }
+    cmd
+}
+
+fn localize_arg(command: &str, arg: Arg) -> Arg {
+    let heading = if arg.is_positional() {
+        Msg::HelpArguments
+    } else {
+        Msg::HelpOptions
+    };
+    let mut help = arg_help(command, arg.get_id().as_str())
+        .map(|help| help.to_string())
+        .or_else(|| arg.get_help().map(|help| help.to_string()))
+        .unwrap_or_default();
+    // Same rules as clap for showing the default and possible values
+    if arg.get_action().takes_values() && !arg.is_hide_default_value_set() {
+        let defaults: Vec<String> = arg
+            .get_default_values()
+            .iter()
+            .map(|value| value.to_string_lossy().into_owned())
+            .collect();
+        if !defaults.is_empty() {
+            help += &format!(" [{}]", Msg::HelpDefault(&defaults.join(" ")));
+        }
+    }
+    if !arg.is_hide_possible_values_set() {
+        let values: Vec<String> = arg
+            .get_possible_values()
+            .iter()
+            .filter(|value| !value.is_hide_set())
+            .map(|value| value.get_name().to_string())
+            .collect();
+        if !values.is_empty() {
+            help += &format!(" [{}]", Msg::PossibleValues(&values.join(", ")));
+        }
+    }
+    arg.help(help.trim_start().to_string())
+        .help_heading(heading.to_string())
+        .hide_default_value(true)
+        .hide_possible_values(true)
+}
+
+/// Replaces the heading of a usage rendered by clap and aligns its other lines to it.
+fn usage(usage: &StyledStr) -> String {
+    let usage = usage.to_string();
+    let usage = usage.strip_prefix("Usage: ").unwrap_or(&usage);
+    let heading = format!("{}: ", Msg::HelpUsage);
+    let indent = format!("\n{}", " ".repeat(heading.chars().count()));
+    heading + &usage.replace("\n       ", &indent)
+}
+
+/// Returns the description of a command, by its name.
+fn about(command: &str) -> Option<Msg<'static>> {
+    Some(match command {
+        "dhbw_roomfinder" => Msg::AboutApp,
+        "find" => Msg::AboutFind,
+        "show" => Msg::AboutShow,
+        "slots" => Msg::AboutSlots,
+        "refresh" => Msg::AboutRefresh,
+        "rooms" => Msg::AboutRooms,
+        "tui" => Msg::AboutTui,
+        "watch" => Msg::AboutWatch,
+        "stats" => Msg::AboutStats,
+        "config" => Msg::AboutConfig,
+        "get" => Msg::AboutConfigGet,
+        "set" => Msg::AboutConfigSet,
+        "help" => Msg::AboutHelp,
+        _ => return None,
+    })
+}
+
+/// Returns the help text of an argument. Some ids are used by several commands
+/// with a different meaning, so the command is matched as well.
+fn arg_help(command: &str, id: &str) -> Option<Msg<'static>> {
+    Some(match (command, id) {
+        (_, "help") => Msg::HelpHelp,
+        (_, "version") => Msg::HelpVersion,
+        (_, "room") => Msg::HelpRoom,
+        (_, "refetch") => Msg::HelpRefetch,
+        (_, "team") => Msg::HelpTeam,
+        (_, "min_capacity") => Msg::HelpMinCapacity,
+        (_, "has") => Msg::HelpHas,
+        (_, "room_type") => Msg::HelpType,
+        (_, "accessible") => Msg::HelpAccessible,
+        (_, "format") => Msg::HelpFormat,
+        (_, "count") => Msg::HelpCount,
+        (_, "all") => Msg::HelpAll,
+        (_, "page") => Msg::HelpPage,
+        (_, "lang") => Msg::HelpLang,
+        (_, "verbose") => Msg::HelpVerbose,
+        (_, "quiet") => Msg::HelpQuiet,
+        (_, "date") => Msg::HelpDay,
+        (_, "time") => Msg::HelpStartTime,
+        (_, "end_time") => Msg::HelpEndTime,
+        (_, "at") => Msg::HelpAt,
+        ("slots", "length") => Msg::HelpSlotLength,
+        ("watch", "length") => Msg::HelpWatchFor,
+        (_, "length") => Msg::HelpFor,
+        ("stats", "from") => Msg::HelpStatsFrom,
+        ("stats", "to") => Msg::HelpStatsTo,
+        (_, "to") => Msg::HelpTo,
+        (_, "weekday") => Msg::HelpWeekday,
+        (_, "weeks") => Msg::HelpWeeks,
+        (_, "until") => Msg::HelpUntil,
+        (_, "partial") => Msg::HelpPartial,
+        (_, "cluster") => Msg::HelpCluster,
+        (_, "max_gap") => Msg::HelpMaxGap,
+        (_, "min_quietness") => Msg::HelpMinQuietness,
+        (_, "rank") => Msg::HelpRank,
+        (_, "origins") => Msg::HelpOrigins,
+        (_, "aggregate") => Msg::HelpAggregate,
+        (_, "show_room") => Msg::HelpShowRoom,
+        (_, "week") => Msg::HelpWeek,
+        (_, "log_file") => Msg::HelpLogFile,
+        (_, "watch_room") => Msg::HelpWatchRoom,
+        (_, "every") => Msg::HelpEvery,
+        (_, "bell") => Msg::HelpBell,
+        (_, "exec") => Msg::HelpExec,
+        (_, "once") => Msg::HelpOnce,
+        (_, "first_hour") => Msg::HelpFirstHour,
+        (_, "last_hour") => Msg::HelpLastHour,
+        (_, "csv") => Msg::HelpCsv,
+        (_, "key") => Msg::HelpKey,
+        (_, "value") => Msg::HelpValue,
+        _ => return None,
+    })
+}
+
+/// Formats the errors of the command line parser with the message catalogue.
+pub struct Localized;
+
+impl ErrorFormatter for Localized {
+    fn format_error(error: &clap::error::Error<Self>) -> StyledStr {
+        let mut text = Msg::CliError(&describe(error)).to_string();
+        for kind in [
+            ContextKind::SuggestedArg,
+            ContextKind::SuggestedSubcommand,
+            ContextKind::SuggestedValue,
+        ] {
+            if let Some(names) = error.get(kind).and_then(quoted) {
+                text += &format!("\n\n  {}", Msg::DidYouMean(&names));
+            }
+        }
+        if let Some(ContextValue::StyledStr(usage)) = error.get(ContextKind::Usage) {
+            text += &format!("\n\n{}", self::usage(usage));
+        }
+        text += &format!("\n\n{}\n", Msg::TryHelp);
+        text.into()
+    }
+}
+
+/// Joins the values of an error context, each in quotes.
+fn quoted(value: &ContextValue) -> Option<String> {
+    match value {
+        ContextValue::String(value) => Some(format!("'{}'", value)),
+        ContextValue::Strings(values) if !values.is_empty() => Some(
+            values
+                .iter()
+                .map(|value| format!("'{}'", value))
+                .collect::<Vec<_>>()
+                .join(", "),
+        ),
+        _ => None,
+    }
+}
+
+/// Joins the accepted values of an error context, without quotes like in the help.
+fn listed(value: Option<&ContextValue>) -> Option<String> {
+    match value {
+        Some(ContextValue::Strings(values)) if !values.is_empty() => Some(values.join(", ")),
+        _ => None,
+    }
+}
+
+/// Describes what is wrong, from the kind and the context of the error.
+fn describe(error: &clap::error::Error<Localized>) -> String {
+    let get = |kind| error.get(kind).and_then(quoted);
+    let number = |kind| match error.get(kind) {
+        Some(ContextValue::Number(number)) => Some(*number),
+        _ => None,
+    };
+    let arg = get(ContextKind::InvalidArg);
+    let value = get(ContextKind::InvalidValue);
+    let described = match error.kind() {
+        ErrorKind::ArgumentConflict => match (arg, get(ContextKind::PriorArg)) {
+            (Some(arg), Some(prior)) if arg == prior => Some(Msg::ArgRepeated(&arg).to_string()),
+            (Some(arg), Some(prior)) => Some(Msg::ArgConflict(&arg, &prior).to_string()),
+            (Some(arg), None) => Some(Msg::ArgConflictAny(&arg).to_string()),
+            (None, prior) => get(ContextKind::InvalidSubcommand).map(|command| {
+                let prior = prior.unwrap_or_default();
+                Msg::SubcommandConflict(&command, &prior).to_string()
+            }),
+        },
+        ErrorKind::NoEquals => arg.map(|arg| Msg::NoEquals(&arg).to_string()),
+        ErrorKind::InvalidValue => match (arg, error.get(ContextKind::InvalidValue)) {
+            (Some(arg), Some(ContextValue::String(empty))) if empty.is_empty() => {
+                Some(Msg::ValueRequired(&arg).to_string())
+            }
+            (Some(arg), Some(_)) => {
+                let mut text = Msg::ArgInvalidValue(&value.unwrap_or_default(), &arg).to_string();
+                if let Some(values) = listed(error.get(ContextKind::ValidValue)) {
+                    text += &format!("\n  [{}]", Msg::PossibleValues(&values));
+                }
+                Some(text)
+            }
+            _ => None,
+        },
+        ErrorKind::InvalidSubcommand => get(ContextKind::InvalidSubcommand)
+            .map(|command| Msg::UnknownSubcommand(&command).to_string()),
+        ErrorKind::MissingRequiredArgument => {
+            arg.map(|args| Msg::MissingArguments(&args).to_string())
+        }
+        ErrorKind::MissingSubcommand => get(ContextKind::InvalidSubcommand).map(|command| {
+            let mut text = Msg::MissingSubcommand(&command).to_string();
+            if let Some(commands) = listed(error.get(ContextKind::ValidSubcommand)) {
+                text += &format!("\n  [{}]", Msg::Subcommands(&commands));
+            }
+            text
+        }),
+        ErrorKind::InvalidUtf8 => Some(Msg::InvalidUtf8.to_string()),
+        ErrorKind::TooManyValues => match (value, arg) {
+            (Some(value), Some(arg)) => Some(Msg::UnexpectedValue(&value, &arg).to_string()),
+            _ => None,
+        },
+        ErrorKind::TooFewValues => match (
+            arg,
+            number(ContextKind::MinValues),
+            number(ContextKind::ActualNumValues),
+        ) {
+            (Some(arg), Some(min), Some(actual)) => {
+                Some(Msg::TooFewValues(&arg, &min, &actual).to_string())
+            }
+            _ => None,
+        },
+        ErrorKind::WrongNumberOfValues => match (
+            arg,
+            number(ContextKind::ExpectedNumValues),
+            number(ContextKind::ActualNumValues),
+        ) {
+            (Some(arg), Some(expected), Some(actual)) => {
+                Some(Msg::WrongNumberOfValues(&arg, &expected, &actual).to_string())
+            }
+            _ => None,
+        },
+        // The reason comes from our own value parsers and is already translated
+        ErrorKind::ValueValidation => match (value, arg) {
+            (Some(value), Some(arg)) => Some(match std::error::Error::source(error) {
+                Some(e) => Msg::ArgInvalidValueBecause(&value, &arg, &e).to_string(),
+                None => Msg::ArgInvalidValue(&value, &arg).to_string(),
+            }),
+            _ => None,
+        },
+        ErrorKind::UnknownArgument => arg.map(|arg| Msg::UnknownArgument(&arg).to_string()),
+        _ => None,
+    };
+    described.unwrap_or_else(|| Msg::InvalidArguments.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+    use dhbw_roomfinder::locale::set_lang;
+
+    fn args(args: &[&str]) -> Vec<OsString> {
+        args.iter().map(OsString::from).collect()
+    }
+
+    #[test]
+    fn reads_the_language_before_parsing() {
+        assert_eq!(lang_arg(&args(&["x", "--lang", "de"])), Some(Lang::De));
+        assert_eq!(lang_arg(&args(&["x", "find", "--lang=en"])), Some(Lang::En));
+        assert_eq!(lang_arg(&args(&["x", "--lang", "xx"])), None);
+        assert_eq!(lang_arg(&args(&["x", "--", "--lang", "de"])), None);
+        assert_eq!(lang_arg(&args(&["x", "-t", "10:00"])), None);
+    }
+
+    #[test]
+    fn every_command_and_argument_has_a_text() {
+        fn check(cmd: &Command) {
+            assert!(about(cmd.get_name()).is_some(), "{}", cmd.get_name());
+            for arg in cmd.get_arguments() {
+                let id = arg.get_id().as_str();
+                assert!(
+                    arg_help(cmd.get_name(), id).is_some(),
+                    "{} {}",
+                    cmd.get_name(),
+                    id
+                );
+            }
+            cmd.get_subcommands().for_each(check);
+        }
+        let mut cmd = crate::Args::command();
+        cmd.build();
+        check(&cmd);
+    }
+
+    #[test]
+    fn formats_parser_errors_in_the_language() {
+        set_lang(Lang::De);
+        let render = |line: &[&str]| {
+            localize(crate::Args::command())
+                .try_get_matches_from(line)
+                .unwrap_err()
+                .apply::<Localized>()
+                .render()
+                .to_string()
+        };
+        let text = render(&["dhbw_roomfinder", "find", "--format", "xml"]);
+        assert!(text.starts_with(
+            "Fehler: ungültiger Wert 'xml' für '--format <FORMAT>'\n  \
+             [mögliche Werte: table, json, ndjson, csv]"
+        ));
+        let text = render(&["dhbw_roomfinder", "find", "-p", "-c", "2"]);
+        assert!(text.starts_with(
+            "Fehler: das Argument '--partial' kann nicht zusammen mit '--cluster <CLUSTER>' verwendet werden"
+        ));
+        assert!(text.contains("\n\nAufruf: dhbw_roomfinder find --partial\n"));
+        let text = render(&["dhbw_roomfinder", "lots"]);
+        assert!(text.contains("Tipp: vielleicht ist 'slots' gemeint"));
+        assert!(
+            text.contains("Aufruf: dhbw_roomfinder [OPTIONS]\n        dhbw_roomfinder <COMMAND>")
+        );
+    }
+}
@@ -0,0 +1,136 @@
+use chrono::{Datelike, Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::{fs, io, path::Path};
+
+use crate::locale::Msg;
+
+const CLOSURES_FILE: &str = "closures.json";
+
+/// Calculates Easter Sunday of the given year (Gregorian calendar, anonymous algorithm).
+pub fn easter_sunday(year: i32) -> NaiveDate {
+    let a = year % 19;
+    let b = year / 100;
+    let c = year % 100;
+    let d = b / 4;
+    let e = b % 4;
+    let f = (b + 8) / 25;
+    let g = (b - f + 1) / 3;
+    let h = (19 * a + b - d - g + 15) % 30;
+    let i = c / 4;
+    let k = c % 4;
+    let l = (32 + 2 * e + 2 * i - h - k) % 7;
+    let m = (a + 11 * h + 22 * l) / 451;
+    let month = (h + l - 7 * m + 114) / 31;
+    let day = (h + l - 7 * m + 114) % 31 + 1;
+    NaiveDate::from_ymd_opt(year, month as u32, day as u32).unwrap()
+}
+
+/// Returns all public holidays of Baden-Württemberg in the given year with their names.
+/// The names are messages, so they are shown in the selected language.
+pub fn bw_holidays(year: i32) -> Vec<(NaiveDate, Msg<'static>)> {
+    let date = |month, day| NaiveDate::from_ymd_opt(year, month, day).unwrap();
+    let easter = easter_sunday(year);
+    vec![
+        (date(1, 1), Msg::NewYear),
+        (date(1, 6), Msg::Epiphany),
+        (easter - Duration::days(2), Msg::GoodFriday),
+        (easter + Duration::days(1), Msg::EasterMonday),
+        (date(5, 1), Msg::LabourDay),
+        (easter + Duration::days(39), Msg::Ascension),
+        (easter + Duration::days(50), Msg::WhitMonday),
+        (easter + Duration::days(60), Msg::CorpusChristi),
+        (date(10, 3), Msg::GermanUnity),
+        (date(11, 1), Msg::AllSaints),
+        (date(12, 25), Msg::ChristmasDay),
+        (date(12, 26), Msg::BoxingDay),
+    ]
+}
+
+/// A range of days on which the campus is closed, e.g. between Christmas and New Year.
+/// `to` defaults to `from` for single closure days.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Closure {
+    pub from: NaiveDate,
+    pub to: Option<NaiveDate>,
+    pub reason: String,
+}
+
+/// Public holidays plus the local campus closure days from closures.json.
+#[derive(Debug, Clone, Default)]
+pub struct Closures {
+    pub closures: Vec<Closure>,
+}
+
+impl Closures {
+    /// Loads the closure days from closures.json.
+    /// A missing file means there are no closure days besides public holidays.
+    pub fn load() -> io::Result<Self> {
+        if !Path::new(CLOSURES_FILE).exists() {
+            return Ok(Closures::default());
+        }
+        let json = fs::read_to_string(CLOSURES_FILE)?;
+        let closures = serde_json::from_str(&json).map_err(io::Error::other)?;
+        Ok(Closures { closures })
+    }
+
+    /// Returns the reason why the campus is closed on the given date,
+    /// or None if it is open.
+    pub fn closed_reason(&self, date: NaiveDate) -> Option<String> {
+        if let Some((_, name)) = bw_holidays(date.year())
+            .into_iter()
+            .find(|(holiday, _)| *holiday == date)
+        {
+            return Some(name.to_string());
+        }
+        self.closures
+            .iter()
+            .find(|closure| closure.from <= date && date <= closure.to.unwrap_or(closure.from))
+            .map(|closure| closure.reason.clone())
+    }
+
+    /// Returns all closed days between `from` and `to` (inclusive) with their reason.
+    pub fn closed_days(&self, from: NaiveDate, to: NaiveDate) -> Vec<(NaiveDate, String)> {
+        let mut closed = Vec::new();
+        let mut date = from;
+        while date <= to {
+            if let Some(reason) = self.closed_reason(date) {
+                closed.push((date, reason));
+            }
+            date += Duration::days(1);
+        }
+        closed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn calculates_easter_sunday() {
+        for (year, month, day) in [
+            (2000, 4, 23),
+            (2008, 3, 23),
+            (2019, 4, 21),
+            (2024, 3, 31),
+            (2025, 4, 20),
+            (2026, 4, 5),
+            (2038, 4, 25),
+        ] {
+            assert_eq!(
+                easter_sunday(year),
+                NaiveDate::from_ymd_opt(year, month, day).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn holidays_close_the_campus() {
+        let closures = Closures::default();
+        let date = |month, day| NaiveDate::from_ymd_opt(2026, month, day).unwrap();
+        assert!(closures.closed_reason(date(4, 3)).is_some());
+        assert!(closures.closed_reason(date(6, 4)).is_some());
+        assert!(closures.closed_reason(date(10, 19)).is_none());
+        assert_eq!(closures.closed_days(date(12, 20), date(12, 31)).len(), 2);
+    }
+}
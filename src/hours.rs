@@ -0,0 +1,268 @@
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, Weekday};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fs, io, path::Path};
+
+use crate::locale::{self, Msg};
+use crate::recurring::parse_weekday;
+
+const OPENING_HOURS_FILE: &str = "opening_hours.json";
+
+/// Opening and closing time of a building on a single day.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct DayHours {
+    pub open: NaiveTime,
+    pub close: NaiveTime,
+}
+
+/// Special opening hours of one day, either for all blocks or a single block.
+/// Without `hours` the building is closed the whole day.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct HoursException {
+    pub date: NaiveDate,
+    pub block: Option<char>,
+    pub hours: Option<DayHours>,
+}
+
+/// Opening hours per weekday, keyed by weekday name (e.g. "mon" or "montag").
+/// Weekdays without an entry are closed.
+pub type WeekHours = BTreeMap<String, DayHours>;
+
+/// Opening hours of the campus buildings.
+/// `default` applies to every block without its own entry in `blocks`,
+/// `exceptions` override both for single dates.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct OpeningHours {
+    pub default: WeekHours,
+    #[serde(default)]
+    pub blocks: BTreeMap<char, WeekHours>,
+    #[serde(default)]
+    pub exceptions: Vec<HoursException>,
+}
+
+impl Default for OpeningHours {
+    /// Monday to Friday 07:00-21:00 and Saturday 08:00-14:00, closed on Sunday.
+    fn default() -> Self {
+        let hours = |open, close| DayHours {
+            open: NaiveTime::from_hms_opt(open, 0, 0).unwrap(),
+            close: NaiveTime::from_hms_opt(close, 0, 0).unwrap(),
+        };
+        let mut default = WeekHours::new();
+        for day in ["mon", "tue", "wed", "thu", "fri"] {
+            default.insert(day.to_owned(), hours(7, 21));
+        }
+        default.insert("sat".to_owned(), hours(8, 14));
+        OpeningHours {
+            default,
+            blocks: BTreeMap::new(),
+            exceptions: Vec::new(),
+        }
+    }
+}
+
+/// Looks up the hours of a weekday in a weekly schedule with German or English weekday keys.
+fn weekday_hours(week: &WeekHours, weekday: Weekday) -> Option<DayHours> {
+    week.iter()
+        .find(|(day, _)| parse_weekday(day) == Ok(weekday))
+        .map(|(_, hours)| *hours)
+}
+
+impl OpeningHours {
+    /// Loads the opening hours from opening_hours.json.
+    /// Falls back to the default opening hours if the file does not exist.
+    pub fn load() -> io::Result<Self> {
+        if !Path::new(OPENING_HOURS_FILE).exists() {
+            return Ok(OpeningHours::default());
+        }
+        let json = fs::read_to_string(OPENING_HOURS_FILE)?;
+        serde_json::from_str(&json).map_err(io::Error::other)
+    }
+
+    /// Returns the opening hours of a block on the given date, or None if it is closed.
+    /// Block specific exceptions take precedence over general exceptions,
+    /// which take precedence over the weekly schedule.
+    /// Blocks are compared ignoring case, both in `blocks` and in the exceptions.
+    /// - `block`: The block of the room, or None for rooms outside the known blocks
+    pub fn hours_on(&self, block: Option<char>, date: NaiveDate) -> Option<DayHours> {
+        let same_block =
+            |other: &char| block.is_some_and(|block| block.eq_ignore_ascii_case(other));
+        let exception = self
+            .exceptions
+            .iter()
+            .find(|exception| {
+                exception.date == date && exception.block.as_ref().is_some_and(same_block)
+            })
+            .or_else(|| {
+                self.exceptions
+                    .iter()
+                    .find(|exception| exception.date == date && exception.block.is_none())
+            });
+        if let Some(exception) = exception {
+            return exception.hours;
+        }
+        let week = self
+            .blocks
+            .iter()
+            .find(|(other, _)| same_block(other))
+            .map_or(&self.default, |(_, week)| week);
+        weekday_hours(week, date.weekday())
+    }
+
+    /// Trims the window to the opening hours of the block: it starts when the block first opens
+    /// and ends when it last closes within the window, so windows may span midnight or several days.
+    /// Returns an explanation if the block is closed during the whole window.
+    pub fn trim(
+        &self,
+        block: Option<char>,
+        start_time: NaiveDateTime,
+        enddatetime: NaiveDateTime,
+    ) -> Result<(NaiveDateTime, NaiveDateTime), String> {
+        let mut open_parts = Vec::new();
+        let mut date = start_time.date();
+        while date.and_time(NaiveTime::MIN) < enddatetime {
+            if let Some(hours) = self.hours_on(block, date) {
+                let start = start_time.max(date.and_time(hours.open));
+                let end = enddatetime.min(date.and_time(hours.close));
+                if start < end {
+                    open_parts.push((start, end));
+                }
+            }
+            date += Duration::days(1);
+        }
+        if let (Some((start, _)), Some((_, end))) = (open_parts.first(), open_parts.last()) {
+            return Ok((*start, *end));
+        }
+
+        let date = start_time.date();
+        if enddatetime > (date + Duration::days(1)).and_time(NaiveTime::MIN) {
+            return Err(Msg::ClosedFromTo(
+                &locale::datetime(start_time),
+                &locale::datetime(enddatetime),
+            )
+            .to_string());
+        }
+        let Some(hours) = self.hours_on(block, date) else {
+            return Err(
+                Msg::ClosedOn(&locale::weekday(date.weekday()), &locale::date(date)).to_string(),
+            );
+        };
+        Err(Msg::OutsideHours(
+            &start_time.format("%H:%M"),
+            &enddatetime.format("%H:%M"),
+            &hours.open.format("%H:%M"),
+            &hours.close.format("%H:%M"),
+        )
+        .to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::locale::{set_lang, Lang};
+
+    fn time(hour: u32, minute: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    fn hours(open: u32, close: u32) -> DayHours {
+        DayHours {
+            open: time(open, 0),
+            close: time(close, 0),
+        }
+    }
+
+    /// 19 October 2026 is a Monday.
+    fn at(day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 10, day)
+            .unwrap()
+            .and_time(time(hour, minute))
+    }
+
+    /// The default hours, block B until 18:00 on Mondays (written in lower case),
+    /// all blocks closed on Tuesday the 20th and block A open late on Wednesday the 21st.
+    fn opening_hours() -> OpeningHours {
+        let mut opening_hours = OpeningHours::default();
+        opening_hours
+            .blocks
+            .insert('b', [("montag".to_owned(), hours(7, 18))].into());
+        opening_hours.exceptions = vec![
+            HoursException {
+                date: at(20, 0, 0).date(),
+                block: None,
+                hours: None,
+            },
+            HoursException {
+                date: at(21, 0, 0).date(),
+                block: Some('a'),
+                hours: Some(hours(9, 23)),
+            },
+        ];
+        opening_hours
+    }
+
+    #[test]
+    fn looks_up_blocks_and_exceptions_ignoring_case() {
+        let opening_hours = opening_hours();
+        let date = |day| at(day, 0, 0).date();
+        assert_eq!(
+            opening_hours.hours_on(Some('A'), date(19)),
+            Some(hours(7, 21))
+        );
+        assert_eq!(
+            opening_hours.hours_on(Some('B'), date(19)),
+            Some(hours(7, 18))
+        );
+        // Block B has no Saturday in its own schedule
+        assert_eq!(opening_hours.hours_on(Some('B'), date(24)), None);
+        assert_eq!(opening_hours.hours_on(Some('A'), date(20)), None);
+        assert_eq!(
+            opening_hours.hours_on(Some('A'), date(21)),
+            Some(hours(9, 23))
+        );
+        assert_eq!(
+            opening_hours.hours_on(Some('C'), date(21)),
+            Some(hours(7, 21))
+        );
+        assert_eq!(opening_hours.hours_on(None, date(25)), None);
+    }
+
+    #[test]
+    fn trims_windows_to_the_opening_hours() {
+        let opening_hours = opening_hours();
+        assert_eq!(
+            opening_hours.trim(Some('A'), at(19, 6, 0), at(19, 22, 0)),
+            Ok((at(19, 7, 0), at(19, 21, 0)))
+        );
+        assert_eq!(
+            opening_hours.trim(Some('B'), at(19, 17, 0), at(19, 19, 0)),
+            Ok((at(19, 17, 0), at(19, 18, 0)))
+        );
+        // Across midnight and the closed Tuesday until Wednesday morning
+        assert_eq!(
+            opening_hours.trim(Some('A'), at(19, 20, 0), at(21, 10, 0)),
+            Ok((at(19, 20, 0), at(21, 10, 0)))
+        );
+        assert_eq!(
+            opening_hours.trim(Some('A'), at(21, 22, 0), at(22, 8, 0)),
+            Ok((at(21, 22, 0), at(22, 8, 0)))
+        );
+    }
+
+    #[test]
+    fn explains_why_a_block_is_closed() {
+        set_lang(Lang::En);
+        let opening_hours = opening_hours();
+        assert_eq!(
+            opening_hours.trim(Some('A'), at(19, 21, 30), at(19, 23, 0)),
+            Err("21:30-23:00 is outside of the opening hours 07:00-21:00".to_owned())
+        );
+        assert_eq!(
+            opening_hours.trim(Some('A'), at(20, 9, 0), at(20, 10, 0)),
+            Err("the building is closed on Tue 2026-10-20".to_owned())
+        );
+        assert_eq!(
+            opening_hours.trim(Some('B'), at(24, 9, 0), at(26, 6, 0)),
+            Err("the building is closed from 2026-10-24 09:00 to 2026-10-26 06:00".to_owned())
+        );
+    }
+}
@@ -9,6 +9,9 @@ use std::{
 use icalendar::{Calendar, CalendarComponent, Component, Event, EventLike};
 
 use crate::loadingbar::Loadingbar;
+use crate::locale::Msg;
+use crate::room::Location;
+use tracing::{debug, info};
 /// Parses a single calendar file, extracting events and associating them with course names.
 /// Updates the events map with merged or new events as needed.
 /// - `filename`: Path to the calendar file (.ics)
@@ -50,23 +53,23 @@ pub fn parse_calendar(filename: &str, events: &mut HashMap<(String, String, Stri
     }
 }
 /// Parses all calendar files in the "courses" directory and groups events by rooms.
-/// Writes one *.ics file per room to the "rooms" directory.
+/// Writes one *.ics file per room to the "rooms" directory, named after the canonical room name.
 /// Uses a loading bar to indicate progress for parsing, grouping, and writing.
 /// Returns Ok(()) on success or an error if file I/O fails.
 pub fn parse_all_calendars() -> Result<(), Box<dyn std::error::Error>> {
     let paths: Vec<_> = fs::read_dir("courses").unwrap().collect();
     let mut events: HashMap<(String, String, String), Event> = HashMap::new();
-    let mut bar = Loadingbar::new("Parsing calendars", paths.len());
+    let mut bar = Loadingbar::new(&Msg::ParsingCalendars.to_string(), paths.len());
     for path in paths {
         let coursename = path.unwrap().path().display().to_string();
         parse_calendar(&coursename, &mut events);
         bar.next();
     }
-    println!();
+    bar.finish();
 
-    let mut locations: HashMap<String, Vec<Event>> = HashMap::new();
+    let mut locations: HashMap<Location, Vec<Event>> = HashMap::new();
     let re = Regex::new(r"([a-gA-G]\d{3})").unwrap();
-    let mut bar = Loadingbar::new("Creating rooms", events.len());
+    let mut bar = Loadingbar::new(&Msg::CreatingRooms.to_string(), events.len());
     for (_key, event) in events {
         bar.next();
         if let Some(location) = event.get_location() {
@@ -77,21 +80,23 @@ pub fn parse_all_calendars() -> Result<(), Box<dyn std::error::Error>> {
                 } else {
                     room.to_string()
                 };
-                if !roomname.is_empty() {
-                    locations
-                        .entry(roomname.to_owned())
-                        .or_insert_with(Vec::new)
-                        .push(event.clone());
+                // Room names are parsed once here, so rooms/ only contains canonical names
+                if let Some(location) = Location::parse(&roomname) {
+                    locations.entry(location).or_default().push(event.clone());
+                } else {
+                    debug!("{}", Msg::SkippedLocation(&roomname));
                 }
             }
         }
     }
-    println!();
+    bar.finish();
     fs::create_dir_all("rooms")?;
-    let mut bar = Loadingbar::new("Writing rooms to file", locations.len());
+    let mut bar = Loadingbar::new(&Msg::WritingRooms.to_string(), locations.len());
+    let rooms = locations.len();
+    let booked: usize = locations.values().map(Vec::len).sum();
     for (location, events) in locations {
         let mut calendar = Calendar::new();
-        calendar.name(&location);
+        calendar.name(&location.to_string());
         for mut event in events {
             let summary = event.get_summary().unwrap_or("");
             let courses = event.property_value("X-KURS").unwrap_or("");
@@ -99,10 +104,14 @@ pub fn parse_all_calendars() -> Result<(), Box<dyn std::error::Error>> {
             event.summary(format!("{} ({})", summary, courses).as_str());
             calendar.push(event);
         }
-        let mut file = File::create(format!("rooms/{}.ics", location.replace("/", "_")))?;
+        let mut file = File::create(format!(
+            "rooms/{}.ics",
+            location.to_string().replace('/', "_")
+        ))?;
         file.write_all(calendar.to_string().as_bytes())?;
         bar.next();
     }
-    println!();
+    bar.finish();
+    info!("{}", Msg::RoomsWritten(&rooms, &booked));
     Ok(())
 }
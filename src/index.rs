@@ -0,0 +1,56 @@
+use std::{collections::BTreeMap, fs, io};
+
+use crate::free::{self, Booking};
+use crate::room::Location;
+use crate::timetable::RoomEvent;
+
+/// Index of all room calendars in the rooms directory, keyed by their parsed location.
+/// Each location maps to the file name (without .ics) of its calendar.
+#[derive(Debug, Clone, Default)]
+pub struct RoomIndex {
+    pub rooms: BTreeMap<Location, String>,
+}
+
+impl RoomIndex {
+    /// Reads the rooms directory and parses every calendar file name into a location.
+    /// Files with empty names are skipped.
+    pub fn load() -> io::Result<Self> {
+        let mut rooms = BTreeMap::new();
+        for file in fs::read_dir("rooms")? {
+            let name = file?
+                .path()
+                .file_name()
+                .unwrap()
+                .display()
+                .to_string()
+                .replace(".ics", "");
+            if let Some(location) = Location::parse(&name) {
+                rooms.insert(location, name);
+            }
+        }
+        Ok(RoomIndex { rooms })
+    }
+
+    /// Returns all known locations in order (numbered rooms first).
+    pub fn locations(&self) -> impl Iterator<Item = &Location> {
+        self.rooms.keys()
+    }
+
+    /// Loads the bookings of a location from its calendar file.
+    /// Unknown locations have no bookings.
+    pub(crate) fn bookings(&self, location: &Location) -> io::Result<Vec<Booking>> {
+        match self.rooms.get(location) {
+            Some(name) => free::bookings(name),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Loads the events of a location from its calendar file.
+    /// Unknown locations have no events.
+    pub(crate) fn events(&self, location: &Location) -> io::Result<Vec<RoomEvent>> {
+        match self.rooms.get(location) {
+            Some(name) => free::events(name),
+            None => Ok(Vec::new()),
+        }
+    }
+}
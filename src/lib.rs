@@ -1,112 +1,480 @@
 use crate::loadingbar::Loadingbar;
-use crate::room::RoomId;
+use crate::locale::Msg;
+use crate::page::{Page, Paged};
+use crate::room::{Location, RoomId};
 
-use chrono::NaiveDateTime;
+use chrono::{NaiveDate, NaiveDateTime};
 use rayon::prelude::*;
 use serde_json::Value;
 use std::fs::{self, File};
 use std::io::{self, Write};
 use std::path::Path;
 use std::time::Duration;
-use std::u32;
+use tracing::{info, warn};
 
 use std::sync::{Arc, Mutex};
 
+pub mod campus;
+pub mod catalogue;
+pub mod cluster;
+pub mod filter;
 mod free;
+pub mod holidays;
+pub mod hours;
 mod icalparser;
+pub mod index;
 mod loadingbar;
+pub mod locale;
+pub mod page;
+pub mod partial;
+pub mod quiet;
+pub mod ranking;
+pub mod recurring;
+pub mod stats;
+pub mod timeexpr;
+pub mod timetable;
+pub mod topology;
+pub mod watch;
 
-mod room;
-use room::calc_distance;
+pub mod room;
+pub mod slots;
+use campus::Campus;
+use catalogue::{Catalogue, RoomInfo};
+use cluster::{Cluster, ClusterQuery};
+use filter::FilterSet;
+use holidays::Closures;
+use hours::OpeningHours;
+use index::RoomIndex;
+use partial::PartialRoom;
+use ranking::{RankReason, Ranking};
+use recurring::{Recurrence, RecurringRoom};
+use slots::RoomSlots;
+use stats::Stats;
+use timetable::TimetableDay;
+use topology::Origins;
 
 const COURSES_FILE: &str = "courses.json";
 
-/// Finds and returns a sorted list of available rooms closest to the given room at the specified time.
+/// A known room with its catalogue metadata and the reason if it is filtered out.
+#[derive(Debug, Clone)]
+pub struct RoomEntry {
+    pub room: Location,
+    pub info: Option<RoomInfo>,
+    pub filtered: Option<String>,
+}
+
+/// A free room found by a search, with its (combined) distance to the origin rooms,
+/// the distance from each origin room, the time until which it stays free
+/// (next booking or closing time) and the start of its next booking.
+/// `quietness` tells how little the neighbours are booked during the window (0.0 to 1.0),
+/// `score` and `reasons` tell how the room was ranked.
+#[derive(Debug, Clone)]
+pub struct FreeRoom {
+    pub room: Location,
+    pub distance: u32,
+    pub distances: Vec<u32>,
+    pub free_until: NaiveDateTime,
+    pub next_event: Option<NaiveDateTime>,
+    pub quietness: f64,
+    pub score: f64,
+    pub reasons: Vec<RankReason>,
+}
+
+/// Downloads all course calendars and rebuilds the room calendars
+/// if requested or if no course list exists yet.
+/// - `reload`: If true, fetches all course and calendar data anew.
+async fn update_calendars(reload: bool) -> Result<(), Box<dyn std::error::Error>> {
+    if !Path::new(COURSES_FILE).exists() || reload {
+        let body = get_courses().await;
+        match body {
+            Ok(text) => {
+                write_file(text)
+                    .unwrap_or_else(|e| panic!("{}", Msg::WritingFileFailed(&COURSES_FILE, &e)));
+            }
+            Err(e) => warn!("{}", Msg::CoursesFailed(&e)),
+        }
+        let json_str = fs::read_to_string(COURSES_FILE)
+            .unwrap_or_else(|e| panic!("{}", Msg::ReadingFileFailed(&COURSES_FILE, &e)));
+        let json: Value = serde_json::from_str(&json_str)?;
+        fs::create_dir_all("courses")?;
+        let courses = json.as_array().unwrap();
+        info!("{}", Msg::CoursesFound(&courses.len()));
+
+        let mut bar = Loadingbar::new(&Msg::LoadingCalendars.to_string(), courses.iter().len());
+
+        for coursename in courses {
+            let name = coursename.as_str().unwrap();
+
+            info!("{}", Msg::Downloading(&name));
+            if let Err(e) = download_with_retry(name, 3).await {
+                warn!("{}", Msg::DownloadFailed(&name, &e));
+            }
+
+            bar.next();
+        }
+
+        bar.finish();
+
+        icalparser::parse_all_calendars()?;
+    }
+    Ok(())
+}
+
+/// Checks whether the campus is closed (public holiday or closure day) on every day of the window.
+/// A window ending at midnight does not count the following day.
+fn is_closed(
+    start_time: NaiveDateTime,
+    enddatetime: NaiveDateTime,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    let closures = Closures::load()?;
+    let last_day = (enddatetime - chrono::Duration::seconds(1)).date();
+    let closed = closures.closed_days(start_time.date(), last_day);
+    Ok(closed.len() as i64 > (last_day - start_time.date()).num_days())
+}
+
+/// Lists all known rooms in index order with their catalogue metadata,
+/// together with the reason if a room is filtered out.
+/// Optionally reloads data and calendars if requested or missing.
+/// - `reload`: If true, fetches all course and calendar data anew.
+/// - `filter`: Include and exclude lists and requirements to check the rooms against.
+pub async fn get_room_list(
+    reload: bool,
+    filter: &FilterSet,
+) -> Result<Vec<RoomEntry>, Box<dyn std::error::Error>> {
+    update_calendars(reload).await?;
+    let catalogue = Catalogue::load()?;
+    Ok(room_index()
+        .locations()
+        .map(|location| {
+            let info = catalogue.get(location).cloned();
+            RoomEntry {
+                filtered: filter.check(location, info.as_ref()).err(),
+                room: location.clone(),
+                info,
+            }
+        })
+        .collect())
+}
+
+/// Loads the index of all rooms that have a calendar in the rooms directory.
+/// Exits the process if the directory cannot be read.
+fn room_index() -> RoomIndex {
+    match RoomIndex::load() {
+        Ok(index) => index,
+        Err(e) => {
+            eprintln!("{}", Msg::ReadingRoomsFailed(&e));
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Finds and returns a ranked list of available rooms at the specified time.
 /// Optionally reloads data and calendars if requested or missing.
+/// The window is trimmed to the opening hours of each room's block.
 /// - `reload`: If true, fetches all course and calendar data anew.
-/// - `roomname`: The reference room name to find closest rooms to.
-/// - `room_count`: Maximum number of available rooms to return.
-/// - `datetime`: The desired date and time for room availability.
-/// Returns a `Vec` of (room name, distance) tuples sorted by distance, or an error if roomname is invalid.
+/// - `origins`: The rooms everyone starts from and how their distances are combined.
+/// - `page`: The page of the free rooms to return.
+/// - `start_time`, `enddatetime`: The desired time window for room availability.
+/// - `filter`: Include and exclude lists and requirements applied before ranking.
+///   If accessible rooms are required, distances follow step-free routes only,
+///   rooms whose neighbours are too busy are left out if a minimum quietness is required.
+/// - `ranking`: Weighted strategies the free rooms are sorted by.
+///
+/// Returns the requested page of the free rooms sorted by their ranking score,
+/// occupied rooms are never part of the results.
+/// The list is empty if the campus is closed during the whole window.
 pub async fn get_rooms(
     reload: bool,
-    roomname: &str,
-    room_count: usize,
+    origins: &Origins,
+    page: &Page,
     start_time: NaiveDateTime,
     enddatetime: NaiveDateTime,
-) -> Result<Vec<(String, u32)>, Box<dyn std::error::Error>> {
-    if let Some(destination_room) = RoomId::from_str(roomname) {
-        if !Path::new(COURSES_FILE).exists() || reload {
-            let body = get_courses().await;
-            match body {
-                Ok(text) => {
-                    write_file(text).expect("Error writing file");
-                }
-                Err(e) => eprintln!("Error: {e}"),
-            }
-            let json_str =
-                fs::read_to_string(COURSES_FILE).expect("Should have been able to read the file");
-            let json: Value = serde_json::from_str(&json_str)?;
-            fs::create_dir_all("courses")?;
-            let courses = json.as_array().unwrap();
+    filter: &FilterSet,
+    ranking: &Ranking,
+) -> Result<Paged<FreeRoom>, Box<dyn std::error::Error>> {
+    update_calendars(reload).await?;
+    if is_closed(start_time, enddatetime)? {
+        return Ok(page.apply(Vec::new()));
+    }
+    let campus = Campus::load()?;
+    let rooms = campus.free_rooms(origins, start_time, enddatetime, filter, ranking);
+    Ok(page.apply(rooms))
+}
 
-            let mut bar = Loadingbar::new("Loading calendars", courses.iter().len());
+/// Ranks rooms by the share of the given time window in which they are free,
+/// so rooms that are only booked for a small part of a long window are not dropped.
+/// Time outside of the opening hours of a room's block and closed days count as taken.
+/// Optionally reloads data and calendars if requested or missing.
+/// - `reload`: If true, fetches all course and calendar data anew.
+/// - `origins`: The rooms used to break ties by distance and how their distances are combined.
+/// - `page`: The page of the rooms to return.
+/// - `start_time`, `enddatetime`: The desired time window for room availability.
+/// - `filter`: Include and exclude lists and requirements applied before ranking.
+///   If accessible rooms are required, distances follow step-free routes only.
+///
+/// Returns the rooms sorted by free fraction (descending), then by distance.
+/// The list is empty if the campus is closed during the whole window.
+pub async fn get_partial_rooms(
+    reload: bool,
+    origins: &Origins,
+    page: &Page,
+    start_time: NaiveDateTime,
+    enddatetime: NaiveDateTime,
+    filter: &FilterSet,
+) -> Result<Paged<PartialRoom>, Box<dyn std::error::Error>> {
+    update_calendars(reload).await?;
+    if is_closed(start_time, enddatetime)? {
+        return Ok(page.apply(Vec::new()));
+    }
+    let campus = Campus::load()?;
+    let (paths, distances) = campus.candidates(origins, filter);
+    let closed = campus.closed_bookings(start_time, enddatetime);
+    let mut rooms: Vec<PartialRoom> = paths
+        .iter()
+        .map(|location| {
+            // Closed days count as taken
+            let bookings = [campus.bookings(location), &closed].concat();
+            let open = campus
+                .opening_hours
+                .trim(location.block(), start_time, enddatetime)
+                .ok();
+            let taken = partial::taken_in_window(&bookings, open, start_time, enddatetime);
+            PartialRoom {
+                room: location.clone(),
+                distance: distances.to(location).0,
+                free_fraction: partial::free_fraction(&taken, start_time, enddatetime),
+                taken,
+            }
+        })
+        .collect();
+    rooms.sort_by(|a, b| {
+        b.free_fraction
+            .total_cmp(&a.free_fraction)
+            .then(a.distance.cmp(&b.distance))
+    });
+    Ok(page.apply(rooms))
+}
 
-            for coursename in courses {
-                let name = coursename.as_str().unwrap();
+/// Finds groups of free rooms next to each other, e.g. for several groups of an exam-prep session.
+/// Optionally reloads data and calendars if requested or missing.
+/// - `reload`: If true, fetches all course and calendar data anew.
+/// - `origins`: The rooms the clusters are ranked by and how their distances are combined.
+/// - `page`: The page of the clusters to return.
+/// - `query`: Number of rooms per cluster and the largest allowed distance between them.
+/// - `start_time`, `enddatetime`: The window all rooms of a cluster have to be free in.
+/// - `filter`: Include and exclude lists and requirements applied before clustering.
+///   If accessible rooms are required, distances follow step-free routes only.
+///
+/// Returns clusters sorted by the distance to their farthest room.
+/// The list is empty if the campus is closed during the whole window.
+pub async fn get_clusters(
+    reload: bool,
+    origins: &Origins,
+    page: &Page,
+    query: &ClusterQuery,
+    start_time: NaiveDateTime,
+    enddatetime: NaiveDateTime,
+    filter: &FilterSet,
+) -> Result<Paged<Cluster>, Box<dyn std::error::Error>> {
+    update_calendars(reload).await?;
+    if is_closed(start_time, enddatetime)? {
+        return Ok(page.apply(Vec::new()));
+    }
+    let campus = Campus::load()?;
+    let (paths, distances) = campus.candidates(origins, filter);
+    let closed = campus.closed_bookings(start_time, enddatetime);
+    let free: Vec<(RoomId, u32)> = paths
+        .iter()
+        .filter_map(|location| {
+            let room = location.room_id()?;
+            let (start, end) = campus
+                .opening_hours
+                .trim(location.block(), start_time, enddatetime)
+                .ok()?;
+            // Rooms that close early are not free for the whole window
+            if (start, end) != (start_time, enddatetime) {
+                return None;
+            }
+            let free = free::is_free_in(campus.bookings(location), start, end)
+                && free::is_free_in(&closed, start, end);
+            free.then(|| (*room, distances.to(location).0))
+        })
+        .collect();
+    let clusters = cluster::find_clusters(
+        &free,
+        query,
+        campus.topology.as_ref(),
+        filter.requirements.accessible,
+    );
+    Ok(page.apply(clusters))
+}
 
-                bar.print(&format!("Downloading: {}.ics", name));
-                let _ = download_with_retry(&name, 3).await;
+/// Finds rooms that are free for as many occurrences of a weekly recurring window as possible.
+/// Optionally reloads data and calendars if requested or missing.
+/// - `reload`: If true, fetches all course and calendar data anew.
+/// - `origins`: The rooms used to break ties by distance and how their distances are combined.
+/// - `page`: The page of the rooms to return.
+/// - `recurrence`: The weekly window to check.
+/// - `filter`: Include and exclude lists and requirements applied before ranking.
+///   If accessible rooms are required, distances follow step-free routes only.
+///
+/// Occurrences on public holidays or campus closure days are skipped,
+/// occurrences outside of the opening hours of a room's block count as taken.
+/// Returns the rooms sorted by the number of free occurrences (descending), then by distance.
+pub async fn get_recurring_rooms(
+    reload: bool,
+    origins: &Origins,
+    page: &Page,
+    recurrence: &Recurrence,
+    filter: &FilterSet,
+) -> Result<Paged<RecurringRoom>, Box<dyn std::error::Error>> {
+    update_calendars(reload).await?;
+    let campus = Campus::load()?;
+    let (paths, distances) = campus.candidates(origins, filter);
+    let occurrences = recurrence.open_occurrences(&campus.closures);
 
-                bar.next();
+    let mut rooms: Vec<RecurringRoom> = paths
+        .iter()
+        .map(|location| {
+            let bookings = campus.bookings(location);
+            let block = location.block();
+            let taken: Vec<_> = occurrences
+                .iter()
+                .filter(
+                    |(start, end)| match campus.opening_hours.trim(block, *start, *end) {
+                        Ok((start, end)) => !free::is_free_in(bookings, start, end),
+                        Err(_) => true,
+                    },
+                )
+                .map(|(start, _)| start.date())
+                .collect();
+            RecurringRoom {
+                room: location.clone(),
+                distance: distances.to(location).0,
+                free: occurrences.len() - taken.len(),
+                taken,
             }
+        })
+        .collect();
+    rooms.sort_by(|a, b| b.free.cmp(&a.free).then(a.distance.cmp(&b.distance)));
+    Ok(page.apply(rooms))
+}
 
-            println!();
+/// Computes room utilization statistics from the parsed room calendars.
+/// Optionally reloads data and calendars if requested or missing.
+/// - `reload`: If true, fetches all course and calendar data anew.
+/// - `from`, `to`: The date range (inclusive) to evaluate.
+/// - `first_hour`, `last_hour`: The hours of each day that are taken into account.
+pub async fn get_stats(
+    reload: bool,
+    from: NaiveDate,
+    to: NaiveDate,
+    first_hour: u32,
+    last_hour: u32,
+) -> Result<Stats, Box<dyn std::error::Error>> {
+    update_calendars(reload).await?;
+    let index = room_index();
+    let paths: Vec<&Location> = index.locations().collect();
 
-            icalparser::parse_all_calendars()?;
-        }
-        let paths: Vec<_> = match fs::read_dir("rooms") {
-            Ok(files) => files.collect(),
-            Err(e) => {
-                eprintln!("Error while reading rooms: {}", e);
-                std::process::exit(1);
-            }
-        };
-
-        let bar = Arc::new(Mutex::new(Loadingbar::new(
-            "Finding rooms",
-            paths.iter().len(),
-        )));
-        let mut min_keys: Vec<(String, u32)> = paths
-            .par_iter()
-            .map(|roomname| {
-                let roomname = roomname
-                    .as_ref()
-                    .unwrap()
-                    .path()
-                    .file_name()
-                    .unwrap()
-                    .display()
-                    .to_string()
-                    .replace(".ics", "");
-
-                if free::is_free(&roomname, start_time, enddatetime) {
-                    let new_distance = calc_distance(&destination_room, &roomname);
-                    let mut bar = bar.lock().unwrap();
-                    bar.next();
-                    return (roomname, new_distance);
-                }
-                let mut bar = bar.lock().unwrap();
-                bar.next();
-                (roomname, u32::MAX)
-            })
-            .collect();
-        min_keys.sort_by_key(|(_, dist)| *dist);
-        println!();
-        Ok(min_keys[0..room_count].to_vec())
-    } else {
-        return Err(format!("{} is not a valid roomname", roomname).into());
+    let bar = Arc::new(Mutex::new(Loadingbar::new(
+        &Msg::LoadingRooms.to_string(),
+        paths.len(),
+    )));
+    let rooms: Vec<(Location, Vec<free::Booking>)> = paths
+        .par_iter()
+        .map(|location| {
+            let bookings = index.bookings(location)?;
+            bar.lock().unwrap().next();
+            Ok(((*location).clone(), bookings))
+        })
+        .collect::<io::Result<_>>()?;
+    bar.lock().unwrap().finish();
+    Ok(stats::compute(&rooms, from, to, first_hour, last_hour))
+}
+
+/// Downloads all course calendars and rebuilds the room calendars, regardless of their age.
+pub async fn refresh() -> Result<(), Box<dyn std::error::Error>> {
+    update_calendars(true).await
+}
+
+/// Builds the timetable of a room for every day of the given date range,
+/// with its events and the free gaps within the opening hours.
+/// Optionally reloads data and calendars if requested or missing.
+/// - `reload`: If true, fetches all course and calendar data anew.
+/// - `room`: The room to show.
+/// - `from`, `to`: The date range (inclusive).
+///
+/// Returns an error if the room has no calendar or it cannot be read.
+pub async fn get_timetable(
+    reload: bool,
+    room: &Location,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<Vec<TimetableDay>, Box<dyn std::error::Error>> {
+    update_calendars(reload).await?;
+    let index = room_index();
+    if !index.rooms.contains_key(room) {
+        return Err(Msg::UnknownRoom(room).to_string().into());
     }
+    let events = index.events(room)?;
+    let opening_hours = OpeningHours::load()?;
+    let closures = Closures::load()?;
+    Ok(from
+        .iter_days()
+        .take_while(|date| *date <= to)
+        .map(|date| {
+            timetable::day(
+                &events,
+                date,
+                opening_hours.hours_on(room.block(), date),
+                closures.closed_reason(date),
+            )
+        })
+        .collect())
+}
+
+/// Lists the free time slots of the rooms nearest to the origin rooms on one day,
+/// within the opening hours of each room's block.
+/// Optionally reloads data and calendars if requested or missing.
+/// - `reload`: If true, fetches all course and calendar data anew.
+/// - `origins`: The rooms the rooms are sorted by and how their distances are combined.
+/// - `page`: The page of the rooms to return.
+/// - `date`: The day to list the slots for.
+/// - `min_length`: Shortest slot that is listed.
+/// - `filter`: Include and exclude lists and requirements applied before listing.
+///
+/// Returns the rooms with at least one slot, sorted by distance.
+/// The list is empty if the campus is closed on that day.
+pub async fn get_free_slots(
+    reload: bool,
+    origins: &Origins,
+    page: &Page,
+    date: NaiveDate,
+    min_length: chrono::Duration,
+    filter: &FilterSet,
+) -> Result<Paged<RoomSlots>, Box<dyn std::error::Error>> {
+    update_calendars(reload).await?;
+    if Closures::load()?.closed_reason(date).is_some() {
+        return Ok(page.apply(Vec::new()));
+    }
+    let campus = Campus::load()?;
+    let (paths, distances) = campus.candidates(origins, filter);
+    let mut rooms: Vec<RoomSlots> = paths
+        .iter()
+        .filter_map(|location| {
+            let hours = campus.opening_hours.hours_on(location.block(), date)?;
+            let (start, end) = (date.and_time(hours.open), date.and_time(hours.close));
+            let taken = free::taken_intervals(campus.bookings(location), start, end);
+            let slots = slots::free_slots(&taken, start, end, min_length);
+            (!slots.is_empty()).then(|| RoomSlots {
+                room: location.clone(),
+                distance: distances.to(location).0,
+                slots,
+            })
+        })
+        .collect();
+    rooms.sort_by_key(|room| room.distance);
+    Ok(page.apply(rooms))
 }
 
 /// Asynchronously fetches course names from the DHBW API endpoint and returns as a JSON string.
@@ -128,6 +496,7 @@ fn write_file(contents: String) -> io::Result<()> {
 /// Downloads an .ics calendar file from the given url, retrying up to max_retries on failure.
 /// - `name`: The course/calendar name.
 /// - `max_retries`: Maximum number of download attempts before error.
+///
 /// Returns Ok on success, or an error after repeated failures.
 async fn download_with_retry(
     name: &str,
@@ -149,16 +518,16 @@ async fn download_with_retry(
                     return Ok(());
                 }
                 Err(e) => {
-                    print!("\rHTTP error (attempt {}): {}\n", attempts, e);
+                    warn!("{}", Msg::HttpError(&attempts, &e));
                 }
             },
             Err(e) => {
-                print!("\rRequest failed (attempt {}): {}\n", attempts, e);
+                warn!("{}", Msg::RequestFailed(&attempts, &e));
             }
         }
 
         if attempts >= max_retries {
-            return Err("Max retries reached".into());
+            return Err(Msg::MaxRetries.to_string().into());
         }
         tokio::time::sleep(Duration::from_secs(2)).await;
     }
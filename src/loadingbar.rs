@@ -1,52 +1,61 @@
-use terminal_size::{terminal_size, Width};
+use std::io::{self, IsTerminal, Write};
+use terminal_size::{terminal_size_of, Width};
+use tracing::Level;
 /// Loadingbar shows a progress bar in the terminal with a customizable label, width and progress tracking.
+/// The bar is written to stderr and only shown if stderr is a terminal and warnings are logged
+/// (not with `-q`), so the results on stdout can be piped into other tools.
 pub struct Loadingbar {
     label: String,
     progress: usize,
     size: usize,
     width: usize,
+    visible: bool,
 }
 
 impl Loadingbar {
     /// Creates a new Loadingbar with the given label and total size (number of steps).
     /// The width is set based on the terminal size.
     pub fn new(label: &str, size: usize) -> Self {
-        let loadingbar = Loadingbar {
+        Loadingbar {
             label: label.to_owned(),
             progress: 0,
             size: size.to_owned(),
-            width: terminal_size()
+            width: terminal_size_of(io::stderr())
                 .map(|(Width(w), _)| w as usize)
                 .unwrap_or(20)
                 / 2,
-        };
-
-        return loadingbar;
+            visible: io::stderr().is_terminal() && tracing::enabled!(Level::WARN),
+        }
     }
 
     /// Advances the progress bar by one step and prints the updated bar.
     pub fn next(&mut self) {
         self.progress += 1;
-        self.print_bar();
+        if self.visible {
+            self.print_bar();
+        }
     }
 
-    /// Prints a message above the loading bar, then reprints the updated bar.
-    pub fn print(&mut self, text: &str) {
-        println!("\r\r\x1b[K{}", text);
-
-        self.print_bar();
+    /// Ends the line of the loading bar once all steps are done.
+    pub fn finish(&self) {
+        if self.visible {
+            eprintln!();
+        }
     }
 
     /// Renders the loading bar with its current progress and label.
     fn print_bar(&self) {
-        let length = (self.progress) * (self.width - 3) / (self.size);
-        print!(
+        let inner = self.width.saturating_sub(3);
+        let length = (self.progress.min(self.size) * inner)
+            .checked_div(self.size)
+            .unwrap_or(inner);
+        eprint!(
             "\r\r\x1b[K{}{}[{}>{}]",
             self.label,
-            " ".repeat(self.width - self.label.len()),
+            " ".repeat(self.width.saturating_sub(self.label.len())),
             "=".repeat(length),
-            " ".repeat(self.width - length - 3)
+            " ".repeat(inner - length)
         );
-        std::io::Write::flush(&mut std::io::stdout()).unwrap();
+        let _ = io::stderr().flush();
     }
 }
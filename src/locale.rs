@@ -0,0 +1,1340 @@
+use chrono::{NaiveDate, NaiveDateTime, Weekday};
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use crate::catalogue::RoomType;
+use crate::timetable::Status;
+
+/// Language of all messages shown to the user.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Lang {
+    #[default]
+    En,
+    De,
+}
+
+impl FromStr for Lang {
+    type Err = String;
+
+    /// Parses "de"/"deutsch" or "en"/"english" and locale names like "de_DE.UTF-8".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Lang::from_name(s).ok_or_else(|| Msg::InvalidLang(&s).to_string())
+    }
+}
+
+impl Lang {
+    /// Matches a language name or a locale like "de_DE.UTF-8".
+    /// Does not format a message, so it can be used while the language is not selected yet.
+    fn from_name(name: &str) -> Option<Lang> {
+        let name = name.trim().to_lowercase();
+        match name.as_str() {
+            "deutsch" | "german" => return Some(Lang::De),
+            "english" | "englisch" => return Some(Lang::En),
+            _ => {}
+        }
+        match name.get(..2) {
+            Some("de") => Some(Lang::De),
+            Some("en") => Some(Lang::En),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Lang {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Lang::En => write!(f, "en"),
+            Lang::De => write!(f, "de"),
+        }
+    }
+}
+
+/// The selected language, 0 until it is set or read from the environment.
+static LANG: AtomicU8 = AtomicU8::new(0);
+
+/// Selects the language of all following messages.
+pub fn set_lang(lang: Lang) {
+    LANG.store(lang as u8 + 1, Ordering::Relaxed);
+}
+
+/// Returns the selected language, by default the one of the environment (see `env_lang`).
+pub fn lang() -> Lang {
+    match LANG.load(Ordering::Relaxed) {
+        0 => {
+            let lang = env_lang();
+            set_lang(lang);
+            lang
+        }
+        2 => Lang::De,
+        _ => Lang::En,
+    }
+}
+
+/// Reads the language from `LC_ALL`, `LC_MESSAGES` or `LANG` (the first one that is set).
+/// German locales select German, everything else (including "C") English.
+pub fn env_lang() -> Lang {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|value| !value.is_empty())
+        .and_then(|value| Lang::from_name(&value))
+        .unwrap_or_default()
+}
+
+/// Formats a date the way the language writes it ("19.10.2026" or "2026-10-19").
+pub fn date(date: NaiveDate) -> String {
+    match lang() {
+        Lang::En => date.format("%Y-%m-%d").to_string(),
+        Lang::De => date.format("%d.%m.%Y").to_string(),
+    }
+}
+
+/// Formats a date without the year ("19.10." or "10-19").
+pub fn day_month(date: NaiveDate) -> String {
+    match lang() {
+        Lang::En => date.format("%m-%d").to_string(),
+        Lang::De => date.format("%d.%m.").to_string(),
+    }
+}
+
+/// Formats a date with the time of day, see `date`.
+pub fn datetime(datetime: NaiveDateTime) -> String {
+    format!("{} {}", date(datetime.date()), datetime.format("%H:%M"))
+}
+
+/// Returns the short name of a weekday ("Mo" or "Mon").
+pub fn weekday(weekday: Weekday) -> &'static str {
+    const DE: [&str; 7] = ["Mo", "Di", "Mi", "Do", "Fr", "Sa", "So"];
+    const EN: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+    let day = weekday.num_days_from_monday() as usize;
+    match lang() {
+        Lang::En => EN[day],
+        Lang::De => DE[day],
+    }
+}
+
+type Arg<'a> = &'a dyn Display;
+
+/// Every message shown to the user, with its arguments.
+/// Displays the text in the selected language, see `en` and `de` for the catalogues.
+#[derive(Clone, Copy)]
+pub enum Msg<'a> {
+    // Room data and downloads
+    NoRoomData(Arg<'a>),
+    ReadingRoomsFailed(Arg<'a>),
+    UnknownRoom(Arg<'a>),
+    CoursesFailed(Arg<'a>),
+    ReadingFileFailed(Arg<'a>, Arg<'a>),
+    WritingFileFailed(Arg<'a>, Arg<'a>),
+    LoadingFailed(Arg<'a>, Arg<'a>),
+    HttpError(Arg<'a>, Arg<'a>),
+    RequestFailed(Arg<'a>, Arg<'a>),
+    MaxRetries,
+    RefreshFailed(Arg<'a>),
+    Refreshed,
+    SearchFailed(Arg<'a>),
+
+    // Log messages
+    LogFileFailed(Arg<'a>, Arg<'a>),
+    CoursesFound(Arg<'a>),
+    Downloading(Arg<'a>),
+    DownloadFailed(Arg<'a>, Arg<'a>),
+    SkippedLocation(Arg<'a>),
+    RoomsWritten(Arg<'a>, Arg<'a>),
+    SearchWindow(Arg<'a>, Arg<'a>),
+
+    // Loading bars
+    LoadingCalendars,
+    ParsingCalendars,
+    CreatingRooms,
+    WritingRooms,
+    LoadingRooms,
+
+    // Room names
+    RoomNameEmpty,
+    InvalidBlock(Arg<'a>),
+    MissingFloor(Arg<'a>),
+    InvalidFloor(Arg<'a>),
+    MissingNumber(Arg<'a>),
+    InvalidNumber(Arg<'a>),
+
+    // Parsing of command line values
+    InvalidLang(Arg<'a>),
+    InvalidDate(Arg<'a>),
+    InvalidWeek(Arg<'a>),
+    InvalidTime(Arg<'a>),
+    RelativeTimeOtherDay(Arg<'a>, Arg<'a>),
+    InvalidDateTime(Arg<'a>),
+    InvalidDuration(Arg<'a>),
+    InvalidWeekday(Arg<'a>),
+    InvalidRoomType(Arg<'a>),
+    InvalidAggregate(Arg<'a>),
+    UnknownStrategy(Arg<'a>),
+    InvalidWeight(Arg<'a>),
+
+    // Command line help
+    AboutApp,
+    AboutFind,
+    AboutShow,
+    AboutSlots,
+    AboutRefresh,
+    AboutRooms,
+    AboutTui,
+    AboutWatch,
+    AboutStats,
+    AboutConfig,
+    AboutConfigGet,
+    AboutConfigSet,
+    AboutHelp,
+    HelpUsage,
+    HelpCommands,
+    HelpArguments,
+    HelpOptions,
+    HelpDefault(Arg<'a>),
+    HelpHelp,
+    HelpVersion,
+    HelpRoom,
+    HelpRefetch,
+    HelpTeam,
+    HelpMinCapacity,
+    HelpHas,
+    HelpType,
+    HelpAccessible,
+    HelpFormat,
+    HelpCount,
+    HelpAll,
+    HelpPage,
+    HelpLang,
+    HelpVerbose,
+    HelpQuiet,
+    HelpDay,
+    HelpStartTime,
+    HelpEndTime,
+    HelpAt,
+    HelpFor,
+    HelpTo,
+    HelpWeekday,
+    HelpWeeks,
+    HelpUntil,
+    HelpPartial,
+    HelpCluster,
+    HelpMaxGap,
+    HelpMinQuietness,
+    HelpRank,
+    HelpOrigins,
+    HelpAggregate,
+    HelpShowRoom,
+    HelpWeek,
+    HelpSlotLength,
+    HelpLogFile,
+    HelpWatchRoom,
+    HelpWatchFor,
+    HelpEvery,
+    HelpBell,
+    HelpExec,
+    HelpOnce,
+    HelpStatsFrom,
+    HelpStatsTo,
+    HelpFirstHour,
+    HelpLastHour,
+    HelpCsv,
+    HelpKey,
+    HelpValue,
+
+    // Command line errors
+    CliError(Arg<'a>),
+    ArgConflict(Arg<'a>, Arg<'a>),
+    ArgConflictAny(Arg<'a>),
+    ArgRepeated(Arg<'a>),
+    SubcommandConflict(Arg<'a>, Arg<'a>),
+    NoEquals(Arg<'a>),
+    ValueRequired(Arg<'a>),
+    ArgInvalidValue(Arg<'a>, Arg<'a>),
+    ArgInvalidValueBecause(Arg<'a>, Arg<'a>, Arg<'a>),
+    PossibleValues(Arg<'a>),
+    Subcommands(Arg<'a>),
+    UnknownSubcommand(Arg<'a>),
+    MissingArguments(Arg<'a>),
+    MissingSubcommand(Arg<'a>),
+    UnexpectedValue(Arg<'a>, Arg<'a>),
+    TooFewValues(Arg<'a>, Arg<'a>, Arg<'a>),
+    WrongNumberOfValues(Arg<'a>, Arg<'a>, Arg<'a>),
+    UnknownArgument(Arg<'a>),
+    InvalidUtf8,
+    InvalidArguments,
+    DidYouMean(Arg<'a>),
+    TryHelp,
+
+    // Configuration
+    ConfigFailed(Arg<'a>),
+    SavingConfigFailed(Arg<'a>),
+    UnknownTeam(Arg<'a>),
+    UnknownKey(Arg<'a>),
+    InvalidValue(Arg<'a>, Arg<'a>),
+    CountAtLeastOne,
+    ValueSet(Arg<'a>),
+
+    // Filters and the room catalogue
+    UserFilter,
+    TeamFilter(Arg<'a>),
+    ExcludedBy(Arg<'a>, Arg<'a>),
+    NotOnIncludeList,
+    NotInCatalogue,
+    OnlySeats(Arg<'a>),
+    UnknownCapacity,
+    Missing(Arg<'a>),
+    NotOfType(RoomType),
+    NotAccessible,
+    RoomTypeName(RoomType),
+    Seats(Arg<'a>),
+    Accessible,
+    Filtered(Arg<'a>),
+
+    // Opening hours and closures
+    ClosedFromTo(Arg<'a>, Arg<'a>),
+    ClosedOn(Arg<'a>, Arg<'a>),
+    OutsideHours(Arg<'a>, Arg<'a>, Arg<'a>, Arg<'a>),
+    OutsideOpeningHours,
+    CampusClosed(Arg<'a>),
+    CampusClosedOn(Arg<'a>, Arg<'a>),
+    WindowTrimmed(Arg<'a>, Arg<'a>, Arg<'a>),
+    NoRoomsAvailable(Arg<'a>),
+    OnDates(Arg<'a>, Arg<'a>),
+    InvalidWindow(Arg<'a>, Arg<'a>),
+    MidnightHint,
+
+    // Public holidays of Baden-Württemberg
+    NewYear,
+    Epiphany,
+    GoodFriday,
+    EasterMonday,
+    LabourDay,
+    Ascension,
+    WhitMonday,
+    CorpusChristi,
+    GermanUnity,
+    AllSaints,
+    ChristmasDay,
+    BoxingDay,
+
+    // Ranking reasons
+    Unreachable,
+    Distance(Arg<'a>),
+    FreeUntil(Arg<'a>),
+    OtherBlock,
+    SameFloor,
+    SameBlock,
+    SameFloorFor(Arg<'a>, Arg<'a>, Arg<'a>),
+    NeighboursBooked(Arg<'a>),
+
+    // Search results
+    Unknown,
+    NoFreeRoomsNear(Arg<'a>),
+    NearestRooms(Arg<'a>),
+    FreeRoom(Arg<'a>, Arg<'a>, Arg<'a>),
+    FreeRoomAggregate(Arg<'a>, Arg<'a>, Arg<'a>, Arg<'a>),
+    Score(Arg<'a>, Arg<'a>),
+    PageEmpty(Arg<'a>, Arg<'a>),
+    PageOf(Arg<'a>, Arg<'a>, Arg<'a>, Arg<'a>),
+    RecurringNeedsTimes,
+    RecurringNeedsEnd,
+    RecurringHeader(Arg<'a>, Arg<'a>, Arg<'a>, Arg<'a>),
+    RecurringRoom(Arg<'a>, Arg<'a>, Arg<'a>, Arg<'a>),
+    RecurringRoomTaken(Arg<'a>, Arg<'a>, Arg<'a>, Arg<'a>, Arg<'a>),
+    MostlyFreeRooms(Arg<'a>),
+    PartialRoom(Arg<'a>, Arg<'a>, Arg<'a>),
+    PartialRoomTaken(Arg<'a>, Arg<'a>, Arg<'a>, Arg<'a>),
+    NoClusters(Arg<'a>, Arg<'a>),
+    ClustersHeader(Arg<'a>, Arg<'a>),
+    Cluster(Arg<'a>, Arg<'a>, Arg<'a>),
+    SlotsHeader(Arg<'a>, Arg<'a>, Arg<'a>),
+    SlotRoom(Arg<'a>, Arg<'a>, Arg<'a>),
+
+    // Timetables
+    DayOpen(Arg<'a>, Arg<'a>),
+    NoEvents,
+    Free,
+    StatusName(Status),
+    Timetable,
+    RoomOn(Arg<'a>, Arg<'a>),
+
+    // Statistics
+    OccupancyHeader(Arg<'a>, Arg<'a>, Arg<'a>, Arg<'a>),
+    PerBlock,
+    PerFloor,
+    PerRoom,
+    StatsWritten(Arg<'a>),
+    InvalidHours(Arg<'a>, Arg<'a>),
+
+    // Watch
+    IntervalTooShort,
+    WatchFree(Arg<'a>),
+    WatchShort(Arg<'a>),
+    WatchBusy(Arg<'a>),
+    WatchClosed(Arg<'a>),
+    HookFailed(Arg<'a>, Arg<'a>),
+    HookNotStarted(Arg<'a>, Arg<'a>),
+
+    // Terminal UI
+    TuiHeader(Arg<'a>, Arg<'a>, Arg<'a>),
+    TuiKeys,
+    Closed,
+    Rooms,
+    FreeRooms,
+    Until(Arg<'a>),
+    OtherRooms,
+}
+
+impl fmt::Display for Msg<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match lang() {
+            Lang::En => en(self, f),
+            Lang::De => de(self, f),
+        }
+    }
+}
+
+/// The English message catalogue.
+fn en(msg: &Msg, f: &mut fmt::Formatter) -> fmt::Result {
+    match msg {
+        Msg::NoRoomData(e) => write!(f, "no room data found ({}), run refresh first", e),
+        Msg::ReadingRoomsFailed(e) => write!(f, "error while reading rooms: {}", e),
+        Msg::UnknownRoom(room) => write!(f, "unknown room: {}", room),
+        Msg::CoursesFailed(e) => write!(f, "failed to download the course list: {}", e),
+        Msg::ReadingFileFailed(file, e) => write!(f, "failed to read {}: {}", file, e),
+        Msg::WritingFileFailed(file, e) => write!(f, "failed to write {}: {}", file, e),
+        Msg::LoadingFailed(file, e) => write!(f, "failed to load {}: {}", file, e),
+        Msg::HttpError(attempt, e) => write!(f, "HTTP error (attempt {}): {}", attempt, e),
+        Msg::RequestFailed(attempt, e) => write!(f, "request failed (attempt {}): {}", attempt, e),
+        Msg::MaxRetries => write!(f, "max retries reached"),
+        Msg::RefreshFailed(e) => write!(f, "refresh failed: {}", e),
+        Msg::Refreshed => write!(f, "room data refreshed"),
+        Msg::SearchFailed(e) => write!(f, "search failed: {}", e),
+
+        Msg::LogFileFailed(path, e) => write!(f, "failed to open log file {}: {}", path, e),
+        Msg::CoursesFound(count) => write!(f, "{} courses found", count),
+        Msg::Downloading(name) => write!(f, "downloading {}.ics", name),
+        Msg::DownloadFailed(name, e) => {
+            write!(f, "failed to download the calendar of {}: {}", name, e)
+        }
+        Msg::SkippedLocation(name) => write!(f, "skipped unknown room name: {}", name),
+        Msg::RoomsWritten(rooms, events) => {
+            write!(f, "wrote {} rooms with {} events", rooms, events)
+        }
+        Msg::SearchWindow(start, end) => write!(f, "search window: {} - {}", start, end),
+
+        Msg::LoadingCalendars => write!(f, "Loading calendars"),
+        Msg::ParsingCalendars => write!(f, "Parsing calendars"),
+        Msg::CreatingRooms => write!(f, "Creating rooms"),
+        Msg::WritingRooms => write!(f, "Writing rooms to file"),
+        Msg::LoadingRooms => write!(f, "Loading rooms"),
+
+        Msg::RoomNameEmpty => write!(f, "room name is empty"),
+        Msg::InvalidBlock(block) => write!(f, "'{}' is not a valid block", block),
+        Msg::MissingFloor(room) => write!(f, "{} has no floor", room),
+        Msg::InvalidFloor(room) => write!(f, "{} has an invalid floor", room),
+        Msg::MissingNumber(room) => write!(f, "{} has no room number", room),
+        Msg::InvalidNumber(room) => write!(f, "{} has an invalid room number", room),
+
+        Msg::InvalidLang(src) => write!(f, "invalid language: {} (de or en)", src),
+        Msg::InvalidDate(src) => write!(f, "invalid date: {}", src),
+        Msg::InvalidWeek(src) => write!(f, "invalid week: {}", src),
+        Msg::InvalidTime(src) => write!(f, "invalid time: {}", src),
+        Msg::RelativeTimeOtherDay(time, day) => {
+            write!(f, "{} is not on the day given with -d ({})", time, day)
+        }
+        Msg::InvalidDateTime(src) => write!(f, "invalid date and time: {}", src),
+        Msg::InvalidDuration(src) => write!(f, "invalid duration: {}", src),
+        Msg::InvalidWeekday(src) => write!(f, "invalid weekday: {}", src),
+        Msg::InvalidRoomType(src) => write!(f, "invalid room type: {}", src),
+        Msg::InvalidAggregate(src) => write!(f, "invalid aggregate: {}", src),
+        Msg::UnknownStrategy(name) => write!(f, "unknown ranking strategy: {}", name),
+        Msg::InvalidWeight(src) => write!(f, "invalid weight: {}", src),
+
+        Msg::AboutApp => write!(f, "Finds free rooms of the DHBW near your room"),
+        Msg::AboutFind => write!(f, "Finds free rooms near the configured room"),
+        Msg::AboutShow => write!(
+            f,
+            "Shows the timetable of a room for a day or an ISO week, with free gaps"
+        ),
+        Msg::AboutSlots => write!(f, "Lists the free time slots of the nearest rooms on a day"),
+        Msg::AboutRefresh => write!(f, "Downloads all calendars and rebuilds the room data"),
+        Msg::AboutRooms => write!(
+            f,
+            "Lists all known rooms and shows which ones are filtered out and why"
+        ),
+        Msg::AboutTui => write!(
+            f,
+            "Browses the rooms over the day in an interactive terminal UI (local data only)"
+        ),
+        Msg::AboutWatch => write!(
+            f,
+            "Watches a room and alerts when it becomes free for long enough (local data only)"
+        ),
+        Msg::AboutStats => write!(
+            f,
+            "Shows room utilization statistics and a weekday/hour heatmap"
+        ),
+        Msg::AboutConfig => write!(f, "Reads or changes the configuration"),
+        Msg::AboutConfigGet => write!(
+            f,
+            "Prints a value, or the whole configuration without a key"
+        ),
+        Msg::AboutConfigSet => write!(f, "Changes a value, e.g. `config set room A266`"),
+        Msg::AboutHelp => write!(
+            f,
+            "Print this message or the help of the given subcommand(s)"
+        ),
+        Msg::HelpUsage => write!(f, "Usage"),
+        Msg::HelpCommands => write!(f, "Commands"),
+        Msg::HelpArguments => write!(f, "Arguments"),
+        Msg::HelpOptions => write!(f, "Options"),
+        Msg::HelpDefault(value) => write!(f, "default: {}", value),
+        Msg::HelpHelp => write!(f, "Print help"),
+        Msg::HelpVersion => write!(f, "Print version"),
+        Msg::HelpRoom => write!(
+            f,
+            "Sets the preferred room, e.g. A244 (saved in config.json)"
+        ),
+        Msg::HelpRefetch => write!(f, "Downloads the calendar data again even if it exists"),
+        Msg::HelpTeam => write!(f, "Also uses the room filters and rooms of this team"),
+        Msg::HelpMinCapacity => write!(f, "Only rooms with at least this many seats"),
+        Msg::HelpHas => write!(f, "Only rooms with this equipment (repeatable)"),
+        Msg::HelpType => write!(
+            f,
+            "Only rooms of this type (lecture_hall, lab, pc_pool, seminar_room)"
+        ),
+        Msg::HelpAccessible => write!(f, "Only accessible rooms that are reachable without stairs"),
+        Msg::HelpFormat => write!(f, "Output format"),
+        Msg::HelpCount => write!(
+            f,
+            "Number of results per page (default: 10 or count from config.json)"
+        ),
+        Msg::HelpAll => write!(f, "Prints all results at once"),
+        Msg::HelpPage => write!(f, "Page of the results"),
+        Msg::HelpLang => write!(f, "Language of the output (de or en)"),
+        Msg::HelpVerbose => write!(f, "Prints more messages on stderr (repeatable)"),
+        Msg::HelpQuiet => write!(f, "Prints fewer messages on stderr (repeatable)"),
+        Msg::HelpDay => write!(f, "Day to search on, e.g. 13.11.2025, tomorrow or fri"),
+        Msg::HelpStartTime => write!(f, "Start of the window, e.g. 09:30 or \"in 30m\""),
+        Msg::HelpEndTime => write!(f, "End of the window on the same day"),
+        Msg::HelpAt => write!(f, "Date and start together, e.g. \"fri 14:00\""),
+        Msg::HelpFor => write!(f, "Length of the window, e.g. 1h30m"),
+        Msg::HelpTo => write!(f, "End of the window with date and time"),
+        Msg::HelpWeekday => write!(f, "Weekday of a recurring search, e.g. tue"),
+        Msg::HelpWeeks => write!(f, "Number of weeks of the recurring search (default: 8)"),
+        Msg::HelpUntil => write!(f, "Last day of the recurring search"),
+        Msg::HelpPartial => write!(
+            f,
+            "Ranks the rooms by the free share of the window and shows the booked parts"
+        ),
+        Msg::HelpCluster => write!(f, "Finds groups of N free rooms next to each other"),
+        Msg::HelpMaxGap => write!(f, "Largest distance between two rooms of a group"),
+        Msg::HelpMinQuietness => write!(
+            f,
+            "Only rooms whose neighbours are mostly free in the window (percent)"
+        ),
+        Msg::HelpRank => write!(
+            f,
+            "Ranking strategies with weights, e.g. nearest=2,quietest"
+        ),
+        Msg::HelpOrigins => write!(f, "Several rooms to start from for a group search"),
+        Msg::HelpAggregate => write!(
+            f,
+            "Combines the distances of the group by their sum or their maximum"
+        ),
+        Msg::HelpShowRoom => write!(f, "Room whose timetable is shown"),
+        Msg::HelpWeek => write!(
+            f,
+            "Shows an ISO week, e.g. 2025-W46 (without a value the week of -d)"
+        ),
+        Msg::HelpSlotLength => write!(f, "Shortest slot in minutes"),
+        Msg::HelpLogFile => write!(f, "Also writes a log to this file"),
+        Msg::HelpWatchRoom => write!(f, "Room to watch"),
+        Msg::HelpWatchFor => write!(f, "How long the room has to be free at least"),
+        Msg::HelpEvery => write!(f, "Time between two checks, at least one minute"),
+        Msg::HelpBell => write!(f, "Rings the terminal bell when the room becomes free"),
+        Msg::HelpExec => write!(f, "Runs this command when the room becomes free"),
+        Msg::HelpOnce => write!(f, "Exits after the first alert"),
+        Msg::HelpStatsFrom => write!(f, "First day (default: today)"),
+        Msg::HelpStatsTo => write!(f, "Last day (default: 6 days later)"),
+        Msg::HelpFirstHour => write!(f, "First hour that is counted"),
+        Msg::HelpLastHour => write!(f, "End of the counted hours"),
+        Msg::HelpCsv => write!(f, "Also saves the statistics as a CSV file"),
+        Msg::HelpKey => write!(f, "Key, nested keys are separated by dots"),
+        Msg::HelpValue => write!(f, "Value, read as JSON or else as text"),
+
+        Msg::CliError(e) => write!(f, "error: {}", e),
+        Msg::ArgConflict(arg, other) => {
+            write!(f, "the argument {} cannot be used with {}", arg, other)
+        }
+        Msg::ArgConflictAny(arg) => write!(
+            f,
+            "the argument {} cannot be used with one or more of the other specified arguments",
+            arg
+        ),
+        Msg::ArgRepeated(arg) => write!(f, "the argument {} cannot be used multiple times", arg),
+        Msg::SubcommandConflict(command, other) => {
+            write!(
+                f,
+                "the subcommand {} cannot be used with {}",
+                command, other
+            )
+        }
+        Msg::NoEquals(arg) => write!(f, "equal sign is needed when assigning values to {}", arg),
+        Msg::ValueRequired(arg) => {
+            write!(f, "a value is required for {} but none was supplied", arg)
+        }
+        Msg::ArgInvalidValue(value, arg) => write!(f, "invalid value {} for {}", value, arg),
+        Msg::ArgInvalidValueBecause(value, arg, e) => {
+            write!(f, "invalid value {} for {}: {}", value, arg, e)
+        }
+        Msg::PossibleValues(values) => write!(f, "possible values: {}", values),
+        Msg::Subcommands(commands) => write!(f, "subcommands: {}", commands),
+        Msg::UnknownSubcommand(command) => write!(f, "unrecognized subcommand {}", command),
+        Msg::MissingArguments(args) => write!(
+            f,
+            "the following required arguments were not provided: {}",
+            args
+        ),
+        Msg::MissingSubcommand(command) => write!(
+            f,
+            "{} requires a subcommand but one was not provided",
+            command
+        ),
+        Msg::UnexpectedValue(value, arg) => write!(
+            f,
+            "unexpected value {} for {} found; no more were expected",
+            value, arg
+        ),
+        Msg::TooFewValues(arg, min, actual) => write!(
+            f,
+            "{} requires at least {} values, {} given",
+            arg, min, actual
+        ),
+        Msg::WrongNumberOfValues(arg, expected, actual) => {
+            write!(f, "{} requires {} values, {} given", arg, expected, actual)
+        }
+        Msg::UnknownArgument(arg) => write!(f, "unexpected argument {} found", arg),
+        Msg::InvalidUtf8 => write!(f, "invalid UTF-8 in the arguments"),
+        Msg::InvalidArguments => write!(f, "invalid arguments"),
+        Msg::DidYouMean(names) => write!(f, "tip: maybe {} was meant", names),
+        Msg::TryHelp => write!(f, "For more information, try '--help'."),
+
+        Msg::ConfigFailed(e) => write!(f, "failed to get config: {}", e),
+        Msg::SavingConfigFailed(e) => write!(f, "failed to save config.json: {}", e),
+        Msg::UnknownTeam(name) => write!(f, "unknown team: {}", name),
+        Msg::UnknownKey(key) => write!(f, "unknown key: {}", key),
+        Msg::InvalidValue(key, e) => write!(f, "invalid value for {}: {}", key, e),
+        Msg::CountAtLeastOne => write!(f, "count must be at least 1"),
+        Msg::ValueSet(key) => write!(f, "{} set", key),
+
+        Msg::UserFilter => write!(f, "user"),
+        Msg::TeamFilter(name) => write!(f, "team {}", name),
+        Msg::ExcludedBy(pattern, source) => write!(f, "excluded by '{}' ({})", pattern, source),
+        Msg::NotOnIncludeList => write!(f, "not on any include list"),
+        Msg::NotInCatalogue => write!(f, "not in room catalogue"),
+        Msg::OnlySeats(seats) => write!(f, "only {} seats", seats),
+        Msg::UnknownCapacity => write!(f, "unknown capacity"),
+        Msg::Missing(equipment) => write!(f, "no {}", equipment),
+        Msg::NotOfType(room_type) => write!(f, "not a {}", Msg::RoomTypeName(*room_type)),
+        Msg::NotAccessible => write!(f, "not accessible"),
+        Msg::RoomTypeName(room_type) => match room_type {
+            RoomType::LectureHall => write!(f, "lecture hall"),
+            RoomType::Lab => write!(f, "lab"),
+            RoomType::PcPool => write!(f, "PC pool"),
+            RoomType::SeminarRoom => write!(f, "seminar room"),
+            RoomType::Other => write!(f, "other room"),
+        },
+        Msg::Seats(seats) => write!(f, "{} seats", seats),
+        Msg::Accessible => write!(f, "accessible"),
+        Msg::Filtered(reason) => write!(f, "filtered: {}", reason),
+
+        Msg::ClosedFromTo(from, to) => write!(f, "the building is closed from {} to {}", from, to),
+        Msg::ClosedOn(weekday, date) => {
+            write!(f, "the building is closed on {} {}", weekday, date)
+        }
+        Msg::OutsideHours(start, end, open, close) => write!(
+            f,
+            "{}-{} is outside of the opening hours {}-{}",
+            start, end, open, close
+        ),
+        Msg::OutsideOpeningHours => write!(f, "outside of the opening hours"),
+        Msg::CampusClosed(reason) => write!(f, "campus closed: {}", reason),
+        Msg::CampusClosedOn(date, reason) => write!(f, "campus closed on {} ({})", date, reason),
+        Msg::WindowTrimmed(start, end, block) => write!(
+            f,
+            "search window trimmed to {}-{} (opening hours of block {})",
+            start, end, block
+        ),
+        Msg::NoRoomsAvailable(reason) => write!(f, "no rooms available: {}", reason),
+        Msg::OnDates(dates, note) => write!(f, "on {}: {}", dates, note),
+        Msg::InvalidWindow(end, start) => write!(
+            f,
+            "invalid search window: the end ({}) is not after the start ({})",
+            end, start
+        ),
+        Msg::MidnightHint => write!(
+            f,
+            "for a window across midnight give the end with a date (e.g. --to \"tomorrow 02:00\") \
+             or a length (--for 4h)"
+        ),
+
+        Msg::NewYear => write!(f, "New Year's Day"),
+        Msg::Epiphany => write!(f, "Epiphany"),
+        Msg::GoodFriday => write!(f, "Good Friday"),
+        Msg::EasterMonday => write!(f, "Easter Monday"),
+        Msg::LabourDay => write!(f, "Labour Day"),
+        Msg::Ascension => write!(f, "Ascension Day"),
+        Msg::WhitMonday => write!(f, "Whit Monday"),
+        Msg::CorpusChristi => write!(f, "Corpus Christi"),
+        Msg::GermanUnity => write!(f, "German Unity Day"),
+        Msg::AllSaints => write!(f, "All Saints' Day"),
+        Msg::ChristmasDay => write!(f, "Christmas Day"),
+        Msg::BoxingDay => write!(f, "Boxing Day"),
+
+        Msg::Unreachable => write!(f, "unreachable"),
+        Msg::Distance(distance) => write!(f, "distance {}", distance),
+        Msg::FreeUntil(time) => write!(f, "free until {}", time),
+        Msg::OtherBlock => write!(f, "other block"),
+        Msg::SameFloor => write!(f, "same floor"),
+        Msg::SameBlock => write!(f, "same block"),
+        Msg::SameFloorFor(floor, block, total) => write!(
+            f,
+            "same floor for {}/{}, same block for {}/{}",
+            floor, total, block, total
+        ),
+        Msg::NeighboursBooked(percent) => write!(f, "neighbours {:.0}% booked", percent),
+
+        Msg::Unknown => write!(f, "unknown"),
+        Msg::NoFreeRoomsNear(rooms) => write!(f, "no free rooms near {} found", rooms),
+        Msg::NearestRooms(rooms) => write!(f, "nearest rooms from {} are:", rooms),
+        Msg::FreeRoom(room, distance, until) => {
+            write!(f, "{} (distance: {}, free until {})", room, distance, until)
+        }
+        Msg::FreeRoomAggregate(room, aggregate, distance, until) => write!(
+            f,
+            "{} ({} distance: {}, free until {})",
+            room, aggregate, distance, until
+        ),
+        Msg::Score(score, reasons) => write!(f, "score {:.2}: {}", score, reasons),
+        Msg::PageEmpty(page, last) => {
+            write!(
+                f,
+                "page {} is empty, the results end on page {}",
+                page, last
+            )
+        }
+        Msg::PageOf(page, pages, total, next) => write!(
+            f,
+            "page {} of {} ({} results), next page with --page {}",
+            page, pages, total, next
+        ),
+        Msg::RecurringNeedsTimes => write!(
+            f,
+            "a recurring query needs a start time (-t or --at) and an end time (-e or --for)"
+        ),
+        Msg::RecurringNeedsEnd => write!(f, "recurrence needs a number of weeks or an end date"),
+        Msg::RecurringHeader(time, from, to, room) => write!(
+            f,
+            "rooms free on {} from {} to {}, nearest to {}:",
+            time, from, to, room
+        ),
+        Msg::RecurringRoom(room, free, total, distance) => {
+            write!(
+                f,
+                "{} (free {}/{}, distance: {})",
+                room, free, total, distance
+            )
+        }
+        Msg::RecurringRoomTaken(room, free, total, distance, taken) => write!(
+            f,
+            "{} (free {}/{}, distance: {}, taken: {})",
+            room, free, total, distance, taken
+        ),
+        Msg::MostlyFreeRooms(room) => write!(f, "mostly free rooms near {} are:", room),
+        Msg::PartialRoom(room, percent, distance) => {
+            write!(f, "{} (free {:.0}%, distance: {})", room, percent, distance)
+        }
+        Msg::PartialRoomTaken(room, percent, distance, taken) => write!(
+            f,
+            "{} (free {:.0}%, distance: {}, taken: {})",
+            room, percent, distance, taken
+        ),
+        Msg::NoClusters(size, gap) => write!(
+            f,
+            "no {} free rooms within a distance of {} found",
+            size, gap
+        ),
+        Msg::ClustersHeader(size, room) => {
+            write!(f, "groups of {} free rooms near {} are:", size, room)
+        }
+        Msg::Cluster(rooms, distance, span) => {
+            write!(f, "{} (distance: {}, span: {})", rooms, distance, span)
+        }
+        Msg::SlotsHeader(length, date, room) => write!(
+            f,
+            "free slots of at least {} minutes on {} near {}:",
+            length, date, room
+        ),
+        Msg::SlotRoom(room, distance, slots) => {
+            write!(f, "{} (distance: {}): {}", room, distance, slots)
+        }
+
+        Msg::DayOpen(open, close) => write!(f, "open {}-{}", open, close),
+        Msg::NoEvents => write!(f, "no events"),
+        Msg::Free => write!(f, "free"),
+        Msg::StatusName(status) => match status {
+            Status::Tentative => write!(f, "tentative"),
+            Status::Confirmed => write!(f, "confirmed"),
+            Status::Cancelled => write!(f, "cancelled"),
+        },
+        Msg::Timetable => write!(f, "Timetable"),
+        Msg::RoomOn(room, date) => write!(f, "{} on {}", room, date),
+
+        Msg::OccupancyHeader(from, to, first, last) => write!(
+            f,
+            "occupancy from {} to {} ({}:00-{}:00):",
+            from, to, first, last
+        ),
+        Msg::PerBlock => write!(f, "per block:"),
+        Msg::PerFloor => write!(f, "per floor:"),
+        Msg::PerRoom => write!(f, "per room (quietest first):"),
+        Msg::StatsWritten(path) => write!(f, "statistics written to {}", path),
+        Msg::InvalidHours(first, last) => write!(
+            f,
+            "invalid hours: --lastHour ({}) is not after --firstHour ({})",
+            last, first
+        ),
+
+        Msg::IntervalTooShort => write!(f, "the interval must be at least one minute"),
+        Msg::WatchFree(until) => write!(f, "is free until {}", until),
+        Msg::WatchShort(until) => write!(f, "is free, but only until {}", until),
+        Msg::WatchBusy(until) => write!(f, "is booked until {}", until),
+        Msg::WatchClosed(reason) => write!(f, "is closed: {}", reason),
+        Msg::HookFailed(command, status) => write!(f, "hook {} failed: {}", command, status),
+        Msg::HookNotStarted(command, e) => write!(f, "failed to run hook {}: {}", command, e),
+
+        Msg::TuiHeader(time, origins, free) => {
+            write!(f, "{}  ·  origin {}  ·  {} free rooms", time, origins, free)
+        }
+        Msg::TuiKeys => write!(
+            f,
+            "arrows/hjkl room  +/- time  [/] day  n now  o origin  q quit"
+        ),
+        Msg::Closed => write!(f, "closed"),
+        Msg::Rooms => write!(f, "Rooms"),
+        Msg::FreeRooms => write!(f, "Free rooms"),
+        Msg::Until(time) => write!(f, "until {}", time),
+        Msg::OtherRooms => write!(f, "other"),
+    }
+}
+
+/// The German message catalogue.
+fn de(msg: &Msg, f: &mut fmt::Formatter) -> fmt::Result {
+    match msg {
+        Msg::NoRoomData(e) => write!(
+            f,
+            "keine Raumdaten gefunden ({}), bitte zuerst refresh ausführen",
+            e
+        ),
+        Msg::ReadingRoomsFailed(e) => write!(f, "Fehler beim Lesen der Räume: {}", e),
+        Msg::UnknownRoom(room) => write!(f, "unbekannter Raum: {}", room),
+        Msg::CoursesFailed(e) => write!(f, "Kursliste konnte nicht geladen werden: {}", e),
+        Msg::ReadingFileFailed(file, e) => write!(f, "{} konnte nicht gelesen werden: {}", file, e),
+        Msg::WritingFileFailed(file, e) => {
+            write!(f, "{} konnte nicht geschrieben werden: {}", file, e)
+        }
+        Msg::LoadingFailed(file, e) => write!(f, "{} konnte nicht geladen werden: {}", file, e),
+        Msg::HttpError(attempt, e) => write!(f, "HTTP-Fehler (Versuch {}): {}", attempt, e),
+        Msg::RequestFailed(attempt, e) => {
+            write!(f, "Anfrage fehlgeschlagen (Versuch {}): {}", attempt, e)
+        }
+        Msg::MaxRetries => write!(f, "maximale Anzahl an Versuchen erreicht"),
+        Msg::RefreshFailed(e) => write!(f, "Aktualisierung fehlgeschlagen: {}", e),
+        Msg::Refreshed => write!(f, "Raumdaten aktualisiert"),
+        Msg::SearchFailed(e) => write!(f, "Suche fehlgeschlagen: {}", e),
+
+        Msg::LogFileFailed(path, e) => {
+            write!(f, "Logdatei {} konnte nicht geöffnet werden: {}", path, e)
+        }
+        Msg::CoursesFound(count) => write!(f, "{} Kurse gefunden", count),
+        Msg::Downloading(name) => write!(f, "Herunterladen: {}.ics", name),
+        Msg::DownloadFailed(name, e) => {
+            write!(
+                f,
+                "Kalender von {} konnte nicht geladen werden: {}",
+                name, e
+            )
+        }
+        Msg::SkippedLocation(name) => write!(f, "unbekannter Raumname übersprungen: {}", name),
+        Msg::RoomsWritten(rooms, events) => {
+            write!(f, "{} Räume mit {} Terminen gespeichert", rooms, events)
+        }
+        Msg::SearchWindow(start, end) => write!(f, "Suchzeitraum: {} - {}", start, end),
+
+        Msg::LoadingCalendars => write!(f, "Kalender laden"),
+        Msg::ParsingCalendars => write!(f, "Kalender einlesen"),
+        Msg::CreatingRooms => write!(f, "Räume erstellen"),
+        Msg::WritingRooms => write!(f, "Räume speichern"),
+        Msg::LoadingRooms => write!(f, "Räume laden"),
+
+        Msg::RoomNameEmpty => write!(f, "Raumname ist leer"),
+        Msg::InvalidBlock(block) => write!(f, "'{}' ist kein gültiger Block", block),
+        Msg::MissingFloor(room) => write!(f, "{} hat kein Stockwerk", room),
+        Msg::InvalidFloor(room) => write!(f, "{} hat ein ungültiges Stockwerk", room),
+        Msg::MissingNumber(room) => write!(f, "{} hat keine Raumnummer", room),
+        Msg::InvalidNumber(room) => write!(f, "{} hat eine ungültige Raumnummer", room),
+
+        Msg::InvalidLang(src) => write!(f, "ungültige Sprache: {} (de oder en)", src),
+        Msg::InvalidDate(src) => write!(f, "ungültiges Datum: {}", src),
+        Msg::InvalidWeek(src) => write!(f, "ungültige Woche: {}", src),
+        Msg::InvalidTime(src) => write!(f, "ungültige Uhrzeit: {}", src),
+        Msg::RelativeTimeOtherDay(time, day) => write!(
+            f,
+            "{} liegt nicht auf dem mit -d angegebenen Tag ({})",
+            time, day
+        ),
+        Msg::InvalidDateTime(src) => write!(f, "ungültiges Datum mit Uhrzeit: {}", src),
+        Msg::InvalidDuration(src) => write!(f, "ungültige Dauer: {}", src),
+        Msg::InvalidWeekday(src) => write!(f, "ungültiger Wochentag: {}", src),
+        Msg::InvalidRoomType(src) => write!(f, "ungültiger Raumtyp: {}", src),
+        Msg::InvalidAggregate(src) => write!(f, "ungültige Zusammenfassung: {}", src),
+        Msg::UnknownStrategy(name) => write!(f, "unbekannte Sortierung: {}", name),
+        Msg::InvalidWeight(src) => write!(f, "ungültige Gewichtung: {}", src),
+
+        Msg::AboutApp => write!(f, "Findet freie Räume der DHBW in der Nähe deines Raums"),
+        Msg::AboutFind => write!(f, "Freie Räume in der Nähe des eingestellten Raums suchen"),
+        Msg::AboutShow => write!(
+            f,
+            "Stundenplan eines Raums für einen Tag oder eine ISO-Woche mit freien Lücken anzeigen"
+        ),
+        Msg::AboutSlots => write!(
+            f,
+            "Freie Zeitfenster der nächstgelegenen Räume an einem Tag auflisten"
+        ),
+        Msg::AboutRefresh => write!(
+            f,
+            "Kalenderdaten neu herunterladen und Räume neu aufbauen"
+        ),
+        Msg::AboutRooms => write!(
+            f,
+            "Alle bekannten Räume auflisten und anzeigen, welche warum herausgefiltert werden"
+        ),
+        Msg::AboutTui => write!(
+            f,
+            "Räume über den Tag in einer interaktiven Terminal-Oberfläche ansehen (nur lokale Daten)"
+        ),
+        Msg::AboutWatch => write!(
+            f,
+            "Raum beobachten und melden, sobald er lange genug frei wird (nur lokale Daten)"
+        ),
+        Msg::AboutStats => write!(
+            f,
+            "Auslastungsstatistik mit Heatmap nach Wochentag und Stunde anzeigen"
+        ),
+        Msg::AboutConfig => write!(f, "Konfiguration anzeigen oder ändern"),
+        Msg::AboutConfigGet => write!(
+            f,
+            "Wert anzeigen, ohne Schlüssel die ganze Konfiguration"
+        ),
+        Msg::AboutConfigSet => write!(f, "Wert ändern, z.B. `config set room A266`"),
+        Msg::AboutHelp => write!(
+            f,
+            "Diese Hilfe oder die Hilfe der angegebenen Befehle anzeigen"
+        ),
+        Msg::HelpUsage => write!(f, "Aufruf"),
+        Msg::HelpCommands => write!(f, "Befehle"),
+        Msg::HelpArguments => write!(f, "Argumente"),
+        Msg::HelpOptions => write!(f, "Optionen"),
+        Msg::HelpDefault(value) => write!(f, "Standard: {}", value),
+        Msg::HelpHelp => write!(f, "Hilfe anzeigen"),
+        Msg::HelpVersion => write!(f, "Programmversion anzeigen"),
+        Msg::HelpRoom => write!(
+            f,
+            "Bevorzugten Raum festlegen, z.B. A244 (wird in der config.json gespeichert)"
+        ),
+        Msg::HelpRefetch => write!(
+            f,
+            "Kalenderdaten neu herunterladen, auch wenn sie schon existieren"
+        ),
+        Msg::HelpTeam => write!(f, "Zusätzlich die Raumfilter und Räume dieses Teams verwenden"),
+        Msg::HelpMinCapacity => write!(f, "Nur Räume mit mindestens so vielen Plätzen"),
+        Msg::HelpHas => write!(f, "Nur Räume mit dieser Ausstattung (mehrfach möglich)"),
+        Msg::HelpType => write!(
+            f,
+            "Nur Räume dieses Typs (lecture_hall, lab, pc_pool, seminar_room)"
+        ),
+        Msg::HelpAccessible => write!(
+            f,
+            "Nur barrierefreie Räume, die ohne Treppen erreichbar sind"
+        ),
+        Msg::HelpFormat => write!(f, "Ausgabeformat"),
+        Msg::HelpCount => write!(
+            f,
+            "Anzahl der Ergebnisse pro Seite (Standard: 10 oder count aus der config.json)"
+        ),
+        Msg::HelpAll => write!(f, "Alle Ergebnisse auf einmal ausgeben"),
+        Msg::HelpPage => write!(f, "Seite der Ergebnisse"),
+        Msg::HelpLang => write!(f, "Sprache der Ausgabe (de oder en)"),
+        Msg::HelpVerbose => write!(f, "Mehr Meldungen auf stderr ausgeben (mehrfach angebbar)"),
+        Msg::HelpQuiet => write!(
+            f,
+            "Weniger Meldungen auf stderr ausgeben (mehrfach angebbar)"
+        ),
+        Msg::HelpDay => write!(f, "Tag, für den gesucht wird, z.B. 13.11.2025, morgen oder fr"),
+        Msg::HelpStartTime => write!(f, "Beginn des Zeitfensters, z.B. 09:30 oder \"in 30m\""),
+        Msg::HelpEndTime => write!(f, "Ende des Zeitfensters am selben Tag"),
+        Msg::HelpAt => write!(f, "Datum und Beginn zusammen, z.B. \"Fr 14:00\""),
+        Msg::HelpFor => write!(f, "Länge des Zeitfensters, z.B. 1h30m"),
+        Msg::HelpTo => write!(f, "Ende des Zeitfensters mit Datum und Uhrzeit"),
+        Msg::HelpWeekday => write!(f, "Wochentag einer wiederkehrenden Suche, z.B. di"),
+        Msg::HelpWeeks => write!(
+            f,
+            "Anzahl der Wochen der wiederkehrenden Suche (Standard: 8)"
+        ),
+        Msg::HelpUntil => write!(f, "Letzter Tag der wiederkehrenden Suche"),
+        Msg::HelpPartial => write!(
+            f,
+            "Räume nach freiem Anteil des Zeitfensters sortieren und belegte Abschnitte anzeigen"
+        ),
+        Msg::HelpCluster => write!(
+            f,
+            "Raumgruppen aus N nebeneinanderliegenden freien Räumen suchen"
+        ),
+        Msg::HelpMaxGap => write!(
+            f,
+            "Größter Abstand zwischen zwei Räumen einer Raumgruppe"
+        ),
+        Msg::HelpMinQuietness => write!(
+            f,
+            "Nur Räume, deren Nachbarräume im Zeitfenster wenig belegt sind (Prozent)"
+        ),
+        Msg::HelpRank => write!(f, "Sortierung mit Gewichten, z.B. nearest=2,quietest"),
+        Msg::HelpOrigins => write!(f, "Mehrere Ausgangsräume für eine Gruppensuche"),
+        Msg::HelpAggregate => write!(
+            f,
+            "Entfernungen der Gruppe summieren oder das Maximum nehmen"
+        ),
+        Msg::HelpShowRoom => write!(f, "Raum, dessen Stundenplan angezeigt wird"),
+        Msg::HelpWeek => write!(
+            f,
+            "ISO-Woche anzeigen, z.B. 2025-W46 (ohne Wert die Woche von -d)"
+        ),
+        Msg::HelpSlotLength => write!(f, "Mindestlänge eines Zeitfensters in Minuten"),
+        Msg::HelpLogFile => write!(f, "Zusätzlich ein Protokoll in diese Datei schreiben"),
+        Msg::HelpWatchRoom => write!(f, "Raum, der beobachtet wird"),
+        Msg::HelpWatchFor => write!(f, "Wie lange der Raum mindestens frei sein muss"),
+        Msg::HelpEvery => write!(
+            f,
+            "Abstand zwischen den Prüfungen, mindestens eine Minute"
+        ),
+        Msg::HelpBell => write!(f, "Terminalglocke läuten, wenn der Raum frei wird"),
+        Msg::HelpExec => write!(f, "Befehl ausführen, wenn der Raum frei wird"),
+        Msg::HelpOnce => write!(f, "Nach der ersten Meldung beenden"),
+        Msg::HelpStatsFrom => write!(f, "Erster Tag (Standard: heute)"),
+        Msg::HelpStatsTo => write!(f, "Letzter Tag (Standard: 6 Tage später)"),
+        Msg::HelpFirstHour => write!(f, "Erste berücksichtigte Stunde"),
+        Msg::HelpLastHour => write!(f, "Ende des berücksichtigten Zeitraums"),
+        Msg::HelpCsv => write!(f, "Statistik zusätzlich als CSV-Datei speichern"),
+        Msg::HelpKey => write!(f, "Schlüssel, verschachtelte Schlüssel mit Punkt"),
+        Msg::HelpValue => write!(f, "Wert, wird als JSON gelesen, sonst als Text"),
+
+        Msg::CliError(e) => write!(f, "Fehler: {}", e),
+        Msg::ArgConflict(arg, other) => write!(
+            f,
+            "das Argument {} kann nicht zusammen mit {} verwendet werden",
+            arg, other
+        ),
+        Msg::ArgConflictAny(arg) => write!(
+            f,
+            "das Argument {} kann nicht mit den anderen angegebenen Argumenten verwendet werden",
+            arg
+        ),
+        Msg::ArgRepeated(arg) => write!(f, "das Argument {} darf nur einmal angegeben werden", arg),
+        Msg::SubcommandConflict(command, other) => write!(
+            f,
+            "der Befehl {} kann nicht zusammen mit {} verwendet werden",
+            command, other
+        ),
+        Msg::NoEquals(arg) => write!(
+            f,
+            "der Wert für {} muss mit einem Gleichheitszeichen angegeben werden",
+            arg
+        ),
+        Msg::ValueRequired(arg) => write!(f, "{} braucht einen Wert, es wurde keiner angegeben", arg),
+        Msg::ArgInvalidValue(value, arg) => write!(f, "ungültiger Wert {} für {}", value, arg),
+        Msg::ArgInvalidValueBecause(value, arg, e) => {
+            write!(f, "ungültiger Wert {} für {}: {}", value, arg, e)
+        }
+        Msg::PossibleValues(values) => write!(f, "mögliche Werte: {}", values),
+        Msg::Subcommands(commands) => write!(f, "Befehle: {}", commands),
+        Msg::UnknownSubcommand(command) => write!(f, "unbekannter Befehl {}", command),
+        Msg::MissingArguments(args) => write!(f, "folgende Argumente fehlen: {}", args),
+        Msg::MissingSubcommand(command) => write!(
+            f,
+            "{} braucht einen Befehl, es wurde keiner angegeben",
+            command
+        ),
+        Msg::UnexpectedValue(value, arg) => write!(
+            f,
+            "unerwarteter Wert {} für {}, es werden keine weiteren Werte erwartet",
+            value, arg
+        ),
+        Msg::TooFewValues(arg, min, actual) => write!(
+            f,
+            "{} braucht mindestens {} Werte, angegeben wurden {}",
+            arg, min, actual
+        ),
+        Msg::WrongNumberOfValues(arg, expected, actual) => write!(
+            f,
+            "{} braucht {} Werte, angegeben wurden {}",
+            arg, expected, actual
+        ),
+        Msg::UnknownArgument(arg) => write!(f, "unerwartetes Argument {}", arg),
+        Msg::InvalidUtf8 => write!(f, "ungültiges UTF-8 in den Argumenten"),
+        Msg::InvalidArguments => write!(f, "ungültige Argumente"),
+        Msg::DidYouMean(names) => write!(f, "Tipp: vielleicht ist {} gemeint", names),
+        Msg::TryHelp => write!(f, "Weitere Informationen mit '--help'."),
+
+        Msg::ConfigFailed(e) => write!(f, "Konfiguration konnte nicht geladen werden: {}", e),
+        Msg::SavingConfigFailed(e) => {
+            write!(f, "config.json konnte nicht gespeichert werden: {}", e)
+        }
+        Msg::UnknownTeam(name) => write!(f, "unbekanntes Team: {}", name),
+        Msg::UnknownKey(key) => write!(f, "unbekannter Schlüssel: {}", key),
+        Msg::InvalidValue(key, e) => write!(f, "ungültiger Wert für {}: {}", key, e),
+        Msg::CountAtLeastOne => write!(f, "count muss mindestens 1 sein"),
+        Msg::ValueSet(key) => write!(f, "{} gesetzt", key),
+
+        Msg::UserFilter => write!(f, "Benutzer"),
+        Msg::TeamFilter(name) => write!(f, "Team {}", name),
+        Msg::ExcludedBy(pattern, source) => {
+            write!(f, "ausgeschlossen durch '{}' ({})", pattern, source)
+        }
+        Msg::NotOnIncludeList => write!(f, "auf keiner Einschlussliste"),
+        Msg::NotInCatalogue => write!(f, "nicht im Raumkatalog"),
+        Msg::OnlySeats(seats) => write!(f, "nur {} Plätze", seats),
+        Msg::UnknownCapacity => write!(f, "Platzanzahl unbekannt"),
+        Msg::Missing(equipment) => write!(f, "ohne {}", equipment),
+        Msg::NotOfType(room_type) => write!(f, "kein {}", Msg::RoomTypeName(*room_type)),
+        Msg::NotAccessible => write!(f, "nicht barrierefrei"),
+        Msg::RoomTypeName(room_type) => match room_type {
+            RoomType::LectureHall => write!(f, "Hörsaal"),
+            RoomType::Lab => write!(f, "Labor"),
+            RoomType::PcPool => write!(f, "PC-Pool"),
+            RoomType::SeminarRoom => write!(f, "Seminarraum"),
+            RoomType::Other => write!(f, "sonstiger Raum"),
+        },
+        Msg::Seats(seats) => write!(f, "{} Plätze", seats),
+        Msg::Accessible => write!(f, "barrierefrei"),
+        Msg::Filtered(reason) => write!(f, "gefiltert: {}", reason),
+
+        Msg::ClosedFromTo(from, to) => {
+            write!(f, "das Gebäude ist von {} bis {} geschlossen", from, to)
+        }
+        Msg::ClosedOn(weekday, date) => {
+            write!(f, "das Gebäude ist am {} {} geschlossen", weekday, date)
+        }
+        Msg::OutsideHours(start, end, open, close) => write!(
+            f,
+            "{}-{} liegt außerhalb der Öffnungszeiten {}-{}",
+            start, end, open, close
+        ),
+        Msg::OutsideOpeningHours => write!(f, "außerhalb der Öffnungszeiten"),
+        Msg::CampusClosed(reason) => write!(f, "Campus geschlossen: {}", reason),
+        Msg::CampusClosedOn(date, reason) => {
+            write!(f, "Campus am {} geschlossen ({})", date, reason)
+        }
+        Msg::WindowTrimmed(start, end, block) => write!(
+            f,
+            "Zeitfenster auf {}-{} gekürzt (Öffnungszeiten von Block {})",
+            start, end, block
+        ),
+        Msg::NoRoomsAvailable(reason) => write!(f, "keine Räume verfügbar: {}", reason),
+        Msg::OnDates(dates, note) => write!(f, "am {}: {}", dates, note),
+        Msg::InvalidWindow(end, start) => write!(
+            f,
+            "ungültiges Zeitfenster: das Ende ({}) liegt nicht nach dem Beginn ({})",
+            end, start
+        ),
+        Msg::MidnightHint => write!(
+            f,
+            "für ein Zeitfenster über Mitternacht das Ende mit Datum \
+             (z.B. --to \"morgen 02:00\") oder eine Dauer (--for 4h) angeben"
+        ),
+
+        Msg::NewYear => write!(f, "Neujahr"),
+        Msg::Epiphany => write!(f, "Heilige Drei Könige"),
+        Msg::GoodFriday => write!(f, "Karfreitag"),
+        Msg::EasterMonday => write!(f, "Ostermontag"),
+        Msg::LabourDay => write!(f, "Tag der Arbeit"),
+        Msg::Ascension => write!(f, "Christi Himmelfahrt"),
+        Msg::WhitMonday => write!(f, "Pfingstmontag"),
+        Msg::CorpusChristi => write!(f, "Fronleichnam"),
+        Msg::GermanUnity => write!(f, "Tag der Deutschen Einheit"),
+        Msg::AllSaints => write!(f, "Allerheiligen"),
+        Msg::ChristmasDay => write!(f, "1. Weihnachtstag"),
+        Msg::BoxingDay => write!(f, "2. Weihnachtstag"),
+
+        Msg::Unreachable => write!(f, "nicht erreichbar"),
+        Msg::Distance(distance) => write!(f, "Entfernung {}", distance),
+        Msg::FreeUntil(time) => write!(f, "frei bis {}", time),
+        Msg::OtherBlock => write!(f, "anderer Block"),
+        Msg::SameFloor => write!(f, "gleiches Stockwerk"),
+        Msg::SameBlock => write!(f, "gleicher Block"),
+        Msg::SameFloorFor(floor, block, total) => write!(
+            f,
+            "gleiches Stockwerk für {}/{}, gleicher Block für {}/{}",
+            floor, total, block, total
+        ),
+        Msg::NeighboursBooked(percent) => write!(f, "Nachbarräume zu {:.0}% belegt", percent),
+
+        Msg::Unknown => write!(f, "unbekannt"),
+        Msg::NoFreeRoomsNear(rooms) => {
+            write!(f, "keine freien Räume in der Nähe von {} gefunden", rooms)
+        }
+        Msg::NearestRooms(rooms) => write!(f, "nächstgelegene freie Räume von {}:", rooms),
+        Msg::FreeRoom(room, distance, until) => {
+            write!(f, "{} (Entfernung: {}, frei bis {})", room, distance, until)
+        }
+        Msg::FreeRoomAggregate(room, aggregate, distance, until) => write!(
+            f,
+            "{} (Entfernung {}: {}, frei bis {})",
+            room, aggregate, distance, until
+        ),
+        Msg::Score(score, reasons) => write!(f, "Bewertung {:.2}: {}", score, reasons),
+        Msg::PageEmpty(page, last) => write!(
+            f,
+            "Seite {} ist leer, die Ergebnisse enden auf Seite {}",
+            page, last
+        ),
+        Msg::PageOf(page, pages, total, next) => write!(
+            f,
+            "Seite {} von {} ({} Ergebnisse), nächste Seite mit --page {}",
+            page, pages, total, next
+        ),
+        Msg::RecurringNeedsTimes => write!(
+            f,
+            "eine wiederkehrende Suche braucht eine Startzeit (-t oder --at) \
+             und eine Endzeit (-e oder --for)"
+        ),
+        Msg::RecurringNeedsEnd => write!(
+            f,
+            "eine wiederkehrende Suche braucht eine Anzahl an Wochen oder ein Enddatum"
+        ),
+        Msg::RecurringHeader(time, from, to, room) => write!(
+            f,
+            "freie Räume am {} vom {} bis {}, nächstgelegen zu {}:",
+            time, from, to, room
+        ),
+        Msg::RecurringRoom(room, free, total, distance) => {
+            write!(
+                f,
+                "{} (frei {}/{}, Entfernung: {})",
+                room, free, total, distance
+            )
+        }
+        Msg::RecurringRoomTaken(room, free, total, distance, taken) => write!(
+            f,
+            "{} (frei {}/{}, Entfernung: {}, belegt: {})",
+            room, free, total, distance, taken
+        ),
+        Msg::MostlyFreeRooms(room) => {
+            write!(f, "überwiegend freie Räume in der Nähe von {}:", room)
+        }
+        Msg::PartialRoom(room, percent, distance) => {
+            write!(
+                f,
+                "{} (frei {:.0}%, Entfernung: {})",
+                room, percent, distance
+            )
+        }
+        Msg::PartialRoomTaken(room, percent, distance, taken) => write!(
+            f,
+            "{} (frei {:.0}%, Entfernung: {}, belegt: {})",
+            room, percent, distance, taken
+        ),
+        Msg::NoClusters(size, gap) => write!(
+            f,
+            "keine {} freien Räume innerhalb einer Entfernung von {} gefunden",
+            size, gap
+        ),
+        Msg::ClustersHeader(size, room) => write!(
+            f,
+            "Gruppen von {} freien Räumen in der Nähe von {}:",
+            size, room
+        ),
+        Msg::Cluster(rooms, distance, span) => {
+            write!(
+                f,
+                "{} (Entfernung: {}, Spannweite: {})",
+                rooms, distance, span
+            )
+        }
+        Msg::SlotsHeader(length, date, room) => write!(
+            f,
+            "freie Zeitfenster von mindestens {} Minuten am {} in der Nähe von {}:",
+            length, date, room
+        ),
+        Msg::SlotRoom(room, distance, slots) => {
+            write!(f, "{} (Entfernung: {}): {}", room, distance, slots)
+        }
+
+        Msg::DayOpen(open, close) => write!(f, "geöffnet {}-{}", open, close),
+        Msg::NoEvents => write!(f, "keine Termine"),
+        Msg::Free => write!(f, "frei"),
+        Msg::StatusName(status) => match status {
+            Status::Tentative => write!(f, "vorläufig"),
+            Status::Confirmed => write!(f, "bestätigt"),
+            Status::Cancelled => write!(f, "abgesagt"),
+        },
+        Msg::Timetable => write!(f, "Stundenplan"),
+        Msg::RoomOn(room, date) => write!(f, "{} am {}", room, date),
+
+        Msg::OccupancyHeader(from, to, first, last) => write!(
+            f,
+            "Auslastung vom {} bis {} ({}:00-{}:00):",
+            from, to, first, last
+        ),
+        Msg::PerBlock => write!(f, "pro Block:"),
+        Msg::PerFloor => write!(f, "pro Stockwerk:"),
+        Msg::PerRoom => write!(f, "pro Raum (ruhigste zuerst):"),
+        Msg::StatsWritten(path) => write!(f, "Statistik gespeichert in {}", path),
+        Msg::InvalidHours(first, last) => write!(
+            f,
+            "ungültige Stunden: --lastHour ({}) liegt nicht nach --firstHour ({})",
+            last, first
+        ),
+
+        Msg::IntervalTooShort => write!(f, "der Abstand muss mindestens eine Minute sein"),
+        Msg::WatchFree(until) => write!(f, "ist frei bis {}", until),
+        Msg::WatchShort(until) => write!(f, "ist frei, aber nur bis {}", until),
+        Msg::WatchBusy(until) => write!(f, "ist belegt bis {}", until),
+        Msg::WatchClosed(reason) => write!(f, "ist geschlossen: {}", reason),
+        Msg::HookFailed(command, status) => {
+            write!(f, "Hook {} fehlgeschlagen: {}", command, status)
+        }
+        Msg::HookNotStarted(command, e) => {
+            write!(f, "Hook {} konnte nicht gestartet werden: {}", command, e)
+        }
+
+        Msg::TuiHeader(time, origins, free) => write!(
+            f,
+            "{}  ·  Ausgangsraum {}  ·  {} freie Räume",
+            time, origins, free
+        ),
+        Msg::TuiKeys => write!(
+            f,
+            "Pfeile/hjkl Raum  +/- Zeit  [/] Tag  n jetzt  o Ausgangsraum  q Ende"
+        ),
+        Msg::Closed => write!(f, "geschlossen"),
+        Msg::Rooms => write!(f, "Räume"),
+        Msg::FreeRooms => write!(f, "Freie Räume"),
+        Msg::Until(time) => write!(f, "bis {}", time),
+        Msg::OtherRooms => write!(f, "sonstige"),
+    }
+}
@@ -0,0 +1,61 @@
+use std::fs::OpenOptions;
+use std::io::{self, IsTerminal, Write};
+use std::path::Path;
+use std::sync::Mutex;
+use tracing_subscriber::filter::{LevelFilter, Targets};
+use tracing_subscriber::fmt;
+use tracing_subscriber::prelude::*;
+
+/// Only messages of this program are logged, not those of the libraries it uses.
+const TARGET: &str = "dhbw_roomfinder";
+
+/// Returns the level of the messages on stderr: warnings and errors by default,
+/// each `-v` adds info, debug and trace messages, `-q` only shows errors and `-qq` nothing.
+pub fn level(verbose: u8, quiet: u8) -> LevelFilter {
+    match i16::from(verbose) - i16::from(quiet) {
+        ..=-2 => LevelFilter::OFF,
+        -1 => LevelFilter::ERROR,
+        0 => LevelFilter::WARN,
+        1 => LevelFilter::INFO,
+        2 => LevelFilter::DEBUG,
+        _ => LevelFilter::TRACE,
+    }
+}
+
+/// Sets up logging to stderr, so stdout only contains results.
+/// If a log file is given, it is appended to with time stamps and at least debug messages.
+/// Returns an error if the log file cannot be opened.
+pub fn init(level: LevelFilter, file: Option<&Path>) -> io::Result<()> {
+    let stderr = fmt::layer()
+        .with_writer(stderr_line)
+        .with_ansi(io::stderr().is_terminal())
+        .without_time()
+        .with_target(false)
+        .with_filter(Targets::new().with_target(TARGET, level));
+    let file = match file {
+        Some(path) => {
+            let file = OpenOptions::new().create(true).append(true).open(path)?;
+            Some(
+                fmt::layer()
+                    .with_writer(Mutex::new(file))
+                    .with_ansi(false)
+                    .with_filter(Targets::new().with_target(TARGET, level.max(LevelFilter::DEBUG))),
+            )
+        }
+        None => None,
+    };
+    tracing_subscriber::registry()
+        .with(stderr)
+        .with(file)
+        .init();
+    Ok(())
+}
+
+/// Returns stderr after clearing the current line, which may hold a loading bar.
+fn stderr_line() -> io::Stderr {
+    let mut stderr = io::stderr();
+    if stderr.is_terminal() {
+        let _ = write!(stderr, "\r\x1b[K");
+    }
+    stderr
+}
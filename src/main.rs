@@ -1,98 +1,1532 @@
-use chrono::{Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use chrono::{
+    Datelike, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, Timelike, Utc, Weekday,
+};
 mod config;
-use clap::Parser;
+mod help;
+mod logging;
+mod output;
+mod tui;
+use clap::{ArgAction, Parser, Subcommand};
 use config::Config;
-use dhbw_roomfinder::get_rooms;
+use dhbw_roomfinder::catalogue::{Requirements, RoomType};
+use dhbw_roomfinder::cluster::ClusterQuery;
+use dhbw_roomfinder::filter::FilterSet;
+use dhbw_roomfinder::holidays::Closures;
+use dhbw_roomfinder::hours::OpeningHours;
+use dhbw_roomfinder::locale::{self, Lang, Msg};
+use dhbw_roomfinder::page::{Page, Paged};
+use dhbw_roomfinder::ranking::{parse_weight, Ranking};
+use dhbw_roomfinder::recurring::{parse_weekday, Recurrence};
+use dhbw_roomfinder::room::{Location, RoomId};
+use dhbw_roomfinder::stats::Occupancy;
+use dhbw_roomfinder::timeexpr::{self, parse_duration, TimeInput};
+use dhbw_roomfinder::timetable::{Entry, Status};
+use dhbw_roomfinder::topology::{Aggregate, Origins};
+use dhbw_roomfinder::watch::{self, WatchState};
+use dhbw_roomfinder::{
+    get_clusters, get_free_slots, get_partial_rooms, get_recurring_rooms, get_room_list, get_rooms,
+    get_stats, get_timetable, refresh,
+};
+use output::{
+    known_distance, print_records, print_records_like, print_value, ClusterRecord, FindRecord,
+    Format, PartialRecord, RecordStream, RecurringRecord, RoomRecord, SlotRecord, StatRecord,
+    TimetableRecord, WatchRecord, Window,
+};
+use std::error::Error;
+use std::ffi::OsString;
+use std::fs;
+use std::io::{self, IsTerminal};
+use std::num::NonZeroUsize;
+use std::path::PathBuf;
+use tracing::{debug, warn};
 
-/// Parses a string into a NaiveDate using either "YYYY-MM-DD" or "DD.MM.YYYY" formats.
-/// Returns an error string if neither format matches.
+/// Parses a date (e.g. "13.11.2025", "morgen", "fr" or "2026-W43-2") relative to today.
 fn parse_date(src: &str) -> Result<NaiveDate, String> {
-    NaiveDate::parse_from_str(src, "%Y-%m-%d")
-        .or_else(|_| NaiveDate::parse_from_str(src, "%d.%m.%Y"))
-        .map_err(|_| format!("invalid date format: {}", src))
+    timeexpr::parse_date(src, Local::now().date_naive())
 }
 
-/// Parses a string into a NaiveTime using "HH:MM" format.
-/// Returns an error string if the format does not match.
-fn parse_time(src: &str) -> Result<NaiveTime, String> {
-    NaiveTime::parse_from_str(src, "%H:%M").map_err(|_| format!("invalid time: {}", src))
+/// Parses an ISO week like "2025-W46", "W46" or "46" into the Monday of that week.
+fn parse_week(src: &str) -> Result<NaiveDate, String> {
+    timeexpr::parse_week(src, Local::now().date_naive())
+}
+
+/// Parses a time of day (e.g. "09:30", "14 Uhr" or "jetzt") or a time relative to now ("in 30m").
+fn parse_time(src: &str) -> Result<TimeInput, String> {
+    timeexpr::parse_time_input(src, Local::now().naive_local())
+}
+
+/// Parses a date and time (e.g. "Fr 14:00" or "morgen 9 Uhr") relative to now.
+fn parse_datetime(src: &str) -> Result<NaiveDateTime, String> {
+    timeexpr::parse_datetime(src, Local::now().naive_local())
 }
 
 #[derive(Parser, Debug)]
-#[command(author, version, about, long_about = None)]
-/// Structure for parsing and holding command-line arguments for room search.
+#[command(author, version, about, long_about = None, args_conflicts_with_subcommands = true)]
+/// Structure for parsing and holding command-line arguments.
+/// Without a subcommand, the arguments of `find` are used.
 struct Args {
-    #[arg(short = 'r', long = "room")]
+    #[arg(short = 'r', long = "room", global = true)]
     room: Option<String>,
-    #[arg(short = 'f', long = "refetch")]
+    #[arg(short = 'f', long = "refetch", global = true)]
     refetch: bool,
+    #[arg(long = "team", global = true)]
+    team: Option<String>,
+    #[arg(long = "min-capacity", global = true)]
+    min_capacity: Option<u32>,
+    #[arg(long = "has", global = true)]
+    has: Vec<String>,
+    #[arg(long = "type", global = true)]
+    room_type: Option<RoomType>,
+    #[arg(short = 'a', long = "accessible", global = true)]
+    accessible: bool,
+    #[arg(long = "format", value_enum, default_value_t = Format::Table, global = true)]
+    format: Format,
+    #[arg(long = "count", global = true)]
+    count: Option<NonZeroUsize>,
+    #[arg(long = "all", global = true, conflicts_with = "count")]
+    all: bool,
+    #[arg(long = "page", global = true, default_value = "1")]
+    page: NonZeroUsize,
+    #[arg(long = "lang", global = true)]
+    lang: Option<Lang>,
+    #[arg(short = 'v', long = "verbose", action = ArgAction::Count, global = true)]
+    verbose: u8,
+    #[arg(short = 'q', long = "quiet", action = ArgAction::Count, global = true, conflicts_with = "verbose")]
+    quiet: u8,
+    #[command(flatten)]
+    find: FindArgs,
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(clap::Args, Debug, Clone)]
+/// Arguments of the room search.
+struct FindArgs {
     #[arg(short = 'd', long = "day", value_parser = parse_date)]
     date: Option<NaiveDate>,
     #[arg(short = 't', long = "startTime", value_parser = parse_time)]
-    time: Option<NaiveTime>,
+    time: Option<TimeInput>,
     #[arg(short = 'e', long = "endTime", value_parser = parse_time)]
-    end_time: Option<NaiveTime>,
+    end_time: Option<TimeInput>,
+    #[arg(long = "at", value_parser = parse_datetime, conflicts_with_all = ["date", "time"])]
+    at: Option<NaiveDateTime>,
+    #[arg(long = "for", value_parser = parse_duration, conflicts_with = "end_time")]
+    length: Option<Duration>,
+    #[arg(long = "to", value_parser = parse_datetime, conflicts_with_all = ["end_time", "length"])]
+    to: Option<NaiveDateTime>,
+    #[arg(short = 'w', long = "weekday", value_parser = parse_weekday, conflicts_with_all = ["partial", "cluster"])]
+    weekday: Option<Weekday>,
+    #[arg(long = "weeks", conflicts_with = "until", value_parser = clap::value_parser!(u32).range(1..))]
+    weeks: Option<u32>,
+    #[arg(long = "until", value_parser = parse_date)]
+    until: Option<NaiveDate>,
+    #[arg(short = 'p', long = "partial", conflicts_with = "cluster")]
+    partial: bool,
+    #[arg(short = 'c', long = "cluster")]
+    cluster: Option<usize>,
+    #[arg(long = "max-gap", default_value_t = 10)]
+    max_gap: u32,
+    // Only the nearest-rooms search ranks and checks the quietness
+    #[arg(long = "min-quietness", value_parser = clap::value_parser!(u8).range(0..=100), conflicts_with_all = ["weekday", "partial", "cluster"])]
+    min_quietness: Option<u8>,
+    #[arg(long = "rank", value_parser = parse_weight, value_delimiter = ',', conflicts_with_all = ["weekday", "partial", "cluster"])]
+    rank: Vec<(String, f64)>,
+    #[arg(long = "origins", value_delimiter = ',')]
+    origins: Vec<RoomId>,
+    #[arg(long = "aggregate")]
+    aggregate: Option<Aggregate>,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+/// Commands of the room finder, `find` is the default.
+enum Command {
+    /// Finds free rooms near the configured room
+    Find(FindArgs),
+    /// Shows the timetable of a room for a day or an ISO week, with free gaps
+    Show {
+        // Own id, otherwise the room would also be taken as the global `--room`
+        #[arg(id = "show_room", value_name = "ROOM")]
+        room: String,
+        #[arg(short = 'd', long = "day", value_parser = parse_date)]
+        date: Option<NaiveDate>,
+        #[arg(long = "week", value_parser = parse_week, num_args = 0..=1)]
+        week: Option<Option<NaiveDate>>,
+    },
+    /// Lists the free time slots of the nearest rooms on a day
+    Slots {
+        #[arg(short = 'd', long = "day", value_parser = parse_date)]
+        date: Option<NaiveDate>,
+        #[arg(long = "length", default_value_t = 30)]
+        length: u32,
+    },
+    /// Downloads all calendars and rebuilds the room data
+    Refresh {
+        #[arg(long = "log-file", value_name = "FILE")]
+        log_file: Option<PathBuf>,
+    },
+    /// Lists all known rooms and shows which ones are filtered out and why
+    Rooms,
+    /// Browses the rooms over the day in an interactive terminal UI (local data only)
+    Tui,
+    /// Watches a room and alerts when it becomes free for long enough (local data only)
+    Watch {
+        #[arg(id = "watch_room", value_name = "ROOM")]
+        room: String,
+        #[arg(long = "for", value_parser = parse_duration, default_value = "30m")]
+        length: Duration,
+        #[arg(long = "every", value_parser = parse_duration, default_value = "1m")]
+        every: Duration,
+        #[arg(long = "bell")]
+        bell: bool,
+        #[arg(long = "exec", value_name = "COMMAND")]
+        exec: Option<String>,
+        #[arg(long = "once")]
+        once: bool,
+    },
+    /// Shows room utilization statistics and a weekday/hour heatmap
+    Stats {
+        #[arg(long = "from", value_parser = parse_date)]
+        from: Option<NaiveDate>,
+        #[arg(long = "to", value_parser = parse_date)]
+        to: Option<NaiveDate>,
+        #[arg(long = "firstHour", default_value_t = 8)]
+        first_hour: u32,
+        #[arg(long = "lastHour", default_value_t = 20)]
+        last_hour: u32,
+        #[arg(long = "csv")]
+        csv: Option<PathBuf>,
+    },
+    /// Reads or changes the configuration
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+}
+
+#[derive(Subcommand, Debug, Clone)]
+/// Actions of the `config` command.
+enum ConfigAction {
+    /// Prints a value, or the whole configuration without a key
+    Get { key: Option<String> },
+    /// Changes a value, e.g. `config set room A266`
+    Set { key: String, value: String },
 }
-mod room;
 
 /// Main asynchronous entry point for the application.
-/// Handles argument parsing, configuration, optional data reloads,
-/// determines the time to search for rooms, and prints results.
+/// Handles argument parsing and configuration and runs the selected command.
 #[tokio::main]
 async fn main() {
-    let args = Args::parse();
+    // The help and parser errors are printed while parsing, so the language is picked first
+    let raw: Vec<OsString> = std::env::args_os().collect();
+    let lang = help::lang_arg(&raw)
+        .or_else(|| Config::get_config(None).ok().and_then(|config| config.lang));
+    if let Some(lang) = lang {
+        locale::set_lang(lang);
+    }
+    let args: Args = help::parse(raw);
+
+    if let Some(lang) = args.lang {
+        locale::set_lang(lang);
+    }
+    let mut config = Config::get_config(args.room.clone()).unwrap_or_else(|e| {
+        eprintln!("{}", Msg::ConfigFailed(&e));
+        std::process::exit(1);
+    });
+    // The command line wins over the configuration, which wins over the environment
+    if let Some(lang) = args.lang.or(config.lang) {
+        locale::set_lang(lang);
+    }
+    let log_file = match &args.command {
+        Some(Command::Refresh { log_file }) => log_file.as_deref(),
+        _ => None,
+    };
+    logging::init(logging::level(args.verbose, args.quiet), log_file).unwrap_or_else(|e| {
+        let path = log_file
+            .map(|path| path.display().to_string())
+            .unwrap_or_default();
+        eprintln!("{}", Msg::LogFileFailed(&path, &e));
+        std::process::exit(1);
+    });
 
-    let mut config = Config::get_config(args.room).expect("failed to get config");
+    let command = args
+        .command
+        .clone()
+        .unwrap_or_else(|| Command::Find(args.find.clone()));
+    let format = args.format;
+    let page = Page {
+        number: args.page.get(),
+        size: if args.all {
+            None
+        } else {
+            Some(
+                args.count
+                    .map_or(config.count.unwrap_or(10), NonZeroUsize::get),
+            )
+        },
+    };
+    match command {
+        Command::Config { action } => edit_config(&mut config, &action, format),
+        Command::Refresh { .. } => refresh_data(&mut config, format).await,
+        Command::Find(find_args) => {
+            let reload = needs_reload(&mut config, args.refetch);
+            let mut filter = filters(&args, &config);
+            filter.requirements.min_quietness = find_args
+                .min_quietness
+                .map(|percent| percent as f64 / 100.0);
+            find(
+                &find_args,
+                args.team.as_deref(),
+                &config,
+                reload,
+                &filter,
+                format,
+                &page,
+            )
+            .await;
+        }
+        Command::Show { room, date, week } => {
+            let reload = needs_reload(&mut config, args.refetch);
+            let date = date.unwrap_or_else(|| Local::now().date_naive());
+            // A week without number means the week of the given day
+            let (from, to) = match week {
+                Some(monday) => {
+                    let monday = monday.unwrap_or_else(|| {
+                        date - Duration::days(date.weekday().num_days_from_monday() as i64)
+                    });
+                    (monday, monday + Duration::days(6))
+                }
+                None => (date, date),
+            };
+            show_room(&room, from, to, reload, format).await;
+        }
+        Command::Slots { date, length } => {
+            let reload = needs_reload(&mut config, args.refetch);
+            let filter = filters(&args, &config);
+            let date = date.unwrap_or_else(|| Local::now().date_naive());
+            let origins = config.origins(args.team.as_deref());
+            list_slots(&origins, reload, date, length, &filter, format, &page).await;
+        }
+        Command::Tui => {
+            let filter = filters(&args, &config);
+            let ranking = Ranking::from_weights(
+                config
+                    .ranking
+                    .iter()
+                    .map(|(name, weight)| (name.as_str(), *weight)),
+            )
+            .unwrap_or_else(|e| {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            });
+            let origins = config.origins(args.team.as_deref());
+            if let Err(e) = tui::run(filter, ranking, origins) {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        }
+        Command::Watch {
+            room,
+            length,
+            every,
+            bell,
+            exec,
+            once,
+        } => {
+            let alert = Alert { bell, exec, once };
+            watch_room(&room, length, every, &alert, format).await;
+        }
+        Command::Rooms => {
+            let reload = needs_reload(&mut config, args.refetch);
+            list_rooms(reload, &filters(&args, &config), format).await;
+        }
+        Command::Stats {
+            from,
+            to,
+            first_hour,
+            last_hour,
+            csv,
+        } => {
+            let reload = needs_reload(&mut config, args.refetch);
+            let from = from.unwrap_or_else(|| Local::now().date_naive());
+            let to = to.unwrap_or(from + Duration::days(6));
+            let last_hour = last_hour.min(24);
+            if last_hour <= first_hour {
+                eprintln!("{}", Msg::InvalidHours(&first_hour, &last_hour));
+                std::process::exit(1);
+            }
+            show_stats(
+                reload,
+                from,
+                to,
+                first_hour,
+                last_hour,
+                csv.as_ref(),
+                format,
+            )
+            .await;
+        }
+    }
+}
 
-    // Decide whether to reload data (forced or older than 1 day)
-    let reload = config.last_updated < Utc::now() - Duration::days(1) || args.refetch;
+/// Decides whether to reload data (forced or older than 1 day)
+/// and remembers the time of the reload in the config.
+fn needs_reload(config: &mut Config, refetch: bool) -> bool {
+    let reload = config.last_updated < Utc::now() - Duration::days(1) || refetch;
     if reload {
         config.last_updated = Utc::now();
         let _ = config.save();
     }
+    reload
+}
+
+/// Builds the room filters of the user and the selected team together with the
+/// requirements given on the command line. Exits if the team is not configured.
+fn filters(args: &Args, config: &Config) -> FilterSet {
+    let mut filter = config.filters(args.team.as_deref()).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    });
+    filter.requirements = Requirements {
+        min_capacity: args.min_capacity,
+        equipment: args.has.clone(),
+        room_type: args.room_type,
+        accessible: args.accessible,
+        min_quietness: None,
+    };
+    filter
+}
+
+/// Loads the closure days, exits if closures.json cannot be read.
+fn load_closures() -> Closures {
+    Closures::load().unwrap_or_else(|e| {
+        eprintln!("{}", Msg::LoadingFailed(&"closures.json", &e));
+        std::process::exit(1);
+    })
+}
+
+/// Formats a distance for the table output,
+/// rooms without a known way from the origin have an unknown distance.
+fn format_distance(distance: u32) -> String {
+    match known_distance(distance) {
+        Some(distance) => distance.to_string(),
+        None => Msg::Unknown.to_string(),
+    }
+}
+
+/// Prints why a search could not be run and exits.
+fn exit_search_failed(e: Box<dyn Error>) -> ! {
+    eprintln!("{}", Msg::SearchFailed(&e));
+    std::process::exit(1);
+}
+
+/// Runs the room search: a recurring, partial or cluster query if requested,
+/// otherwise the nearest free rooms in the window, and prints the results.
+async fn find(
+    args: &FindArgs,
+    team: Option<&str>,
+    config: &Config,
+    reload: bool,
+    filter: &FilterSet,
+    format: Format,
+    page: &Page,
+) {
+    // Command line origins replace the team's rooms
+    let mut origins = if args.origins.is_empty() {
+        config.origins(team)
+    } else {
+        Origins {
+            rooms: args.origins.clone(),
+            aggregate: Aggregate::Sum,
+        }
+    };
+    if let Some(aggregate) = args.aggregate {
+        origins.aggregate = aggregate;
+    }
+    if let Some(weekday) = args.weekday {
+        find_recurring(
+            args, weekday, config, &origins, reload, filter, format, page,
+        )
+        .await;
+        return;
+    }
+    let names: Vec<String> = origins.rooms.iter().map(|room| room.to_string()).collect();
+
+    let (start_time, enddatetime) = search_window(args).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    });
+    debug!(
+        "{}",
+        Msg::SearchWindow(
+            &locale::datetime(start_time),
+            &locale::datetime(enddatetime)
+        )
+    );
+    // Only give up if the campus is closed during the whole window
+    let closures = load_closures();
+    let last_day = (enddatetime - Duration::seconds(1)).date();
+    let closed = closures.closed_days(start_time.date(), last_day);
+    if !closed.is_empty() {
+        print_closed(&closed, format);
+        if closed.len() as i64 > (last_day - start_time.date()).num_days() {
+            print_no_results(args, &names, format);
+            return;
+        }
+    }
+    if !check_opening_hours(config, start_time, enddatetime, format) {
+        print_no_results(args, &names, format);
+        return;
+    }
+    if args.partial {
+        find_partial(
+            &origins,
+            reload,
+            start_time,
+            enddatetime,
+            filter,
+            format,
+            page,
+        )
+        .await;
+        return;
+    }
+    if let Some(size) = args.cluster {
+        let query = ClusterQuery {
+            size,
+            max_gap: args.max_gap,
+        };
+        find_clusters(
+            &origins,
+            reload,
+            &query,
+            start_time,
+            enddatetime,
+            filter,
+            format,
+            page,
+        )
+        .await;
+        return;
+    }
+    // Command line weights replace the configured ones
+    let weights = if args.rank.is_empty() {
+        config.ranking.clone().into_iter().collect()
+    } else {
+        args.rank.clone()
+    };
+    let ranking = Ranking::from_weights(
+        weights
+            .iter()
+            .map(|(name, weight)| (name.as_str(), *weight)),
+    )
+    .unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    });
+    // Query and print nearest available rooms
+    let mut paged = get_rooms(
+        reload,
+        &origins,
+        page,
+        start_time,
+        enddatetime,
+        filter,
+        &ranking,
+    )
+    .await
+    .unwrap_or_else(|e| exit_search_failed(e));
+    let keys = std::mem::take(&mut paged.items);
+    if format != Format::Table {
+        let window = Window {
+            start: start_time,
+            end: enddatetime,
+        };
+        let records: Vec<FindRecord> = keys
+            .iter()
+            .map(|room| FindRecord {
+                room: room.room.to_string(),
+                distance: known_distance(room.distance),
+                distances: names
+                    .iter()
+                    .zip(&room.distances)
+                    .map(|(name, distance)| (name.clone(), known_distance(*distance).into()))
+                    .collect(),
+                free_until: room.free_until,
+                next_event: room.next_event,
+                quietness: room.quietness,
+                score: room.score,
+                window,
+            })
+            .collect();
+        print_records_like(format, &records, &FindRecord::empty(&names));
+        print_page(&paged, format);
+        return;
+    }
+    // Times on other days than the start of the window get a date
+    let until = |time: NaiveDateTime| {
+        if time.date() == start_time.date() {
+            time.format("%H:%M").to_string()
+        } else {
+            locale::datetime(time)
+        }
+    };
+    if paged.total == 0 {
+        println!("{}", Msg::NoFreeRoomsNear(&names.join(", ")));
+        return;
+    }
+    println!("{}", Msg::NearestRooms(&names.join(", ")));
+    for room in keys {
+        if origins.rooms.len() > 1 {
+            println!(
+                "{}",
+                Msg::FreeRoomAggregate(
+                    &room.room,
+                    &origins.aggregate,
+                    &format_distance(room.distance),
+                    &until(room.free_until)
+                )
+            );
+            let each: Vec<String> = names
+                .iter()
+                .zip(&room.distances)
+                .map(|(name, distance)| format!("{}: {}", name, format_distance(*distance)))
+                .collect();
+            println!("    {}", each.join(", "));
+        } else {
+            println!(
+                "{}",
+                Msg::FreeRoom(
+                    &room.room,
+                    &format_distance(room.distance),
+                    &until(room.free_until)
+                )
+            );
+        }
+        // Explain the ranking if it is not the plain distance
+        if !weights.is_empty() && !room.reasons.is_empty() {
+            let reasons: Vec<String> = room
+                .reasons
+                .iter()
+                .map(|reason| {
+                    format!(
+                        "{} {:.2}x{} ({})",
+                        reason.strategy, reason.score, reason.weight, reason.detail
+                    )
+                })
+                .collect();
+            println!("    {}", Msg::Score(&room.score, &reasons.join(", ")));
+        }
+    }
+    print_page(&paged, format);
+}
+
+/// Joins the origin rooms for the headers of the search results.
+fn origin_names(origins: &Origins) -> String {
+    let names: Vec<String> = origins.rooms.iter().map(|room| room.to_string()).collect();
+    names.join(", ")
+}
 
-    // Default to current local datetime
-    let mut start_time = Local::now().naive_local();
-    let mut enddatetime = (start_time.date() + Duration::days(1))
-        .and_hms_opt(0, 0, 0)
+/// Prints an empty result in the record type of the search,
+/// so machine readable output keeps its schema.
+/// - `origins`: The names of the origin rooms, each has a distance column.
+fn print_no_results(args: &FindArgs, origins: &[String], format: Format) {
+    if args.partial {
+        print_records::<PartialRecord>(format, &[]);
+    } else if args.cluster.is_some() {
+        print_records::<ClusterRecord>(format, &[]);
+    } else {
+        print_records_like(format, &[], &FindRecord::empty(origins));
+    }
+}
+
+/// Builds the search window from the command line.
+/// The start defaults to now (or the start of the day for other days), the end to the end of the start day.
+/// Windows may span midnight or several days if the end is given with a date (`--to`) or as a length (`--for`).
+/// Returns an error if the window ends before it starts.
+fn search_window(args: &FindArgs) -> Result<(NaiveDateTime, NaiveDateTime), String> {
+    let now = Local::now()
+        .naive_local()
+        .with_second(0)
+        .and_then(|now| now.with_nanosecond(0))
         .unwrap();
+    let start = match (args.at, args.time, args.date) {
+        (Some(at), _, _) => at,
+        // Times relative to now bring their own date
+        (_, Some(TimeInput::Relative(at)), Some(date)) if at.date() != date => {
+            return Err(
+                Msg::RelativeTimeOtherDay(&locale::datetime(at), &locale::date(date)).to_string(),
+            );
+        }
+        (_, Some(time), date) => time.on(date.unwrap_or(now.date())),
+        (_, None, date) => {
+            let date = date.unwrap_or(now.date());
+            let default_time = if date == now.date() {
+                now.time()
+            } else {
+                NaiveTime::MIN
+            };
+            date.and_time(default_time)
+        }
+    };
+    let end = match (args.to, args.end_time, args.length) {
+        (Some(to), _, _) => to,
+        (_, Some(time), _) => time.on(start.date()),
+        (_, _, Some(length)) => start.checked_add_signed(length).ok_or_else(|| {
+            Msg::InvalidDuration(&format!("{}m", length.num_minutes())).to_string()
+        })?,
+        _ => (start.date() + Duration::days(1)).and_time(NaiveTime::MIN),
+    };
+    if end > start {
+        return Ok((start, end));
+    }
+    let mut message =
+        Msg::InvalidWindow(&locale::datetime(end), &locale::datetime(start)).to_string();
+    if matches!(args.end_time, Some(TimeInput::OfDay(_))) {
+        message.push_str(&format!("\n{}", Msg::MidnightHint));
+    }
+    Err(message)
+}
+
+/// Downloads all calendars and rebuilds the room data, then remembers the time in the config.
+async fn refresh_data(config: &mut Config, format: Format) {
+    refresh().await.unwrap_or_else(|e| {
+        eprintln!("{}", Msg::RefreshFailed(&e));
+        std::process::exit(1);
+    });
+    config.last_updated = Utc::now();
+    let _ = config.save();
+    format.note(&Msg::Refreshed.to_string());
+}
+
+/// Prints or changes a configuration value and saves the changed configuration.
+fn edit_config(config: &mut Config, action: &ConfigAction, format: Format) {
+    match action {
+        ConfigAction::Get { key } => match config.get(key.as_deref()) {
+            Ok(value) => print_value(format, &value),
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        },
+        ConfigAction::Set { key, value } => {
+            if let Err(e) = config.set(key, value) {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+            config.save().unwrap_or_else(|e| {
+                eprintln!("{}", Msg::SavingConfigFailed(&e));
+                std::process::exit(1);
+            });
+            format.note(&Msg::ValueSet(key).to_string());
+        }
+    }
+}
+
+/// Prints the timetable of a room for every day of the range:
+/// events with time, title, courses and status, and the free gaps within the opening hours.
+/// Free gaps are highlighted in green on a terminal.
+async fn show_room(room: &str, from: NaiveDate, to: NaiveDate, reload: bool, format: Format) {
+    let Some(location) = Location::parse(room) else {
+        eprintln!("{}", Msg::RoomNameEmpty);
+        std::process::exit(1);
+    };
+    let days = get_timetable(reload, &location, from, to)
+        .await
+        .unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        });
+    if format != Format::Table {
+        let mut records = Vec::new();
+        for day in &days {
+            let record = |kind, start, end| TimetableRecord {
+                room: location.to_string(),
+                date: day.date,
+                kind,
+                start,
+                end,
+                title: None,
+                courses: Vec::new(),
+                status: None,
+            };
+            if let Some(reason) = &day.closed {
+                records.push(TimetableRecord {
+                    title: Some(reason.clone()),
+                    ..record("closed", None, None)
+                });
+            }
+            for entry in &day.entries {
+                records.push(match entry {
+                    Entry::Event(event) => TimetableRecord {
+                        title: Some(event.title.clone()),
+                        courses: event.courses.clone(),
+                        status: event.status.map(|status| status.to_string()),
+                        ..record("event", Some(event.start), Some(event.end))
+                    },
+                    Entry::Free { start, end } => record("free", Some(*start), Some(*end)),
+                });
+            }
+        }
+        print_records(format, &records);
+        return;
+    }
+    let color = io::stdout().is_terminal();
+    for day in days {
+        print!(
+            "{}, {} {}",
+            location,
+            locale::weekday(day.date.weekday()),
+            locale::date(day.date)
+        );
+        match (&day.closed, day.hours) {
+            (Some(reason), _) => println!(" ({})", Msg::CampusClosed(reason)),
+            (None, Some(hours)) => println!(
+                " ({})",
+                Msg::DayOpen(&hours.open.format("%H:%M"), &hours.close.format("%H:%M"))
+            ),
+            (None, None) => println!(" ({})", Msg::Closed),
+        }
+        if day.entries.is_empty() {
+            println!("  {}", Msg::NoEvents);
+        }
+        for entry in &day.entries {
+            match entry {
+                Entry::Event(event) => {
+                    let mut line = format!(
+                        "  {}-{}  {}",
+                        event.start.format("%H:%M"),
+                        event.end.format("%H:%M"),
+                        event.title
+                    );
+                    if !event.courses.is_empty() {
+                        line.push_str(&format!(" [{}]", event.courses.join(", ")));
+                    }
+                    if let Some(status) = event.status.filter(|status| *status != Status::Confirmed)
+                    {
+                        line.push_str(&format!(" ({})", Msg::StatusName(status)));
+                    }
+                    println!("{}", line);
+                }
+                Entry::Free { start, end } => {
+                    let line = format!(
+                        "  {}-{}  {}",
+                        start.format("%H:%M"),
+                        end.format("%H:%M"),
+                        Msg::Free
+                    );
+                    if color {
+                        println!("\x1b[32m{}\x1b[0m", line);
+                    } else {
+                        println!("{}", line);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Prints the free time slots of the rooms nearest to the configured or team rooms on a day.
+/// - `length`: Shortest slot in minutes that is listed
+async fn list_slots(
+    origins: &Origins,
+    reload: bool,
+    date: NaiveDate,
+    length: u32,
+    filter: &FilterSet,
+    format: Format,
+    page: &Page,
+) {
+    let closures = load_closures();
+    if let Some(reason) = closures.closed_reason(date) {
+        print_closed(&[(date, reason)], format);
+        print_records::<SlotRecord>(format, &[]);
+        return;
+    }
+    let mut paged = get_free_slots(
+        reload,
+        origins,
+        page,
+        date,
+        Duration::minutes(length as i64),
+        filter,
+    )
+    .await
+    .unwrap_or_else(|e| exit_search_failed(e));
+    let rooms = std::mem::take(&mut paged.items);
+    if format != Format::Table {
+        let records: Vec<SlotRecord> = rooms
+            .iter()
+            .flat_map(|room| {
+                room.slots.iter().map(|(start, end)| SlotRecord {
+                    room: room.room.to_string(),
+                    distance: known_distance(room.distance),
+                    start: *start,
+                    end: *end,
+                })
+            })
+            .collect();
+        print_records(format, &records);
+        print_page(&paged, format);
+        return;
+    }
     println!(
-        "{},{};{},{}",
-        start_time.date(),
-        start_time.time(),
-        enddatetime.date(),
-        enddatetime.time()
+        "{}",
+        Msg::SlotsHeader(&length, &locale::date(date), &origin_names(origins))
     );
+    for room in rooms {
+        let slots: Vec<String> = room
+            .slots
+            .iter()
+            .map(|(start, end)| format!("{}-{}", start.format("%H:%M"), end.format("%H:%M")))
+            .collect();
+        println!(
+            "{}",
+            Msg::SlotRoom(
+                &room.room,
+                &format_distance(room.distance),
+                &slots.join(", ")
+            )
+        );
+    }
+    print_page(&paged, format);
+}
+
+/// How `watch` alerts when the room becomes free.
+struct Alert {
+    bell: bool,
+    exec: Option<String>,
+    once: bool,
+}
 
-    // Apply user-specified date, if any
-    if let Some(date) = args.date {
-        println!("{}", date);
-        start_time = NaiveDateTime::new(date, start_time.time());
+/// Checks a room against the local calendars every `every` and prints each change of its state.
+/// When it becomes free for at least `length`, rings the terminal bell
+/// and runs the hook if requested.
+/// Stops after the first alert with `--once`, otherwise runs until interrupted.
+async fn watch_room(room: &str, length: Duration, every: Duration, alert: &Alert, format: Format) {
+    let Some(location) = Location::parse(room) else {
+        eprintln!("{}", Msg::RoomNameEmpty);
+        std::process::exit(1);
+    };
+    if every < Duration::minutes(1) {
+        eprintln!("{}", Msg::IntervalTooShort);
+        std::process::exit(1);
     }
-    // Apply user-specified time, if any
-    if let Some(time) = args.time {
-        println!("{}", time);
-        start_time = NaiveDateTime::new(start_time.date(), time);
+    let mut stream = RecordStream::new(format);
+    let mut last: Option<WatchState> = None;
+    loop {
+        let now = Local::now().naive_local().with_nanosecond(0).unwrap();
+        let state = watch::check(&location, now, length).unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        });
+        if last.as_ref() != Some(&state) {
+            let time = |time: NaiveDateTime| {
+                if time.date() == now.date() {
+                    time.format("%H:%M").to_string()
+                } else {
+                    locale::datetime(time)
+                }
+            };
+            let (name, until, reason, text) = match &state {
+                WatchState::Free { until } => (
+                    "free",
+                    Some(*until),
+                    None,
+                    Msg::WatchFree(&time(*until)).to_string(),
+                ),
+                WatchState::Short { until } => (
+                    "short",
+                    Some(*until),
+                    None,
+                    Msg::WatchShort(&time(*until)).to_string(),
+                ),
+                WatchState::Busy { until } => (
+                    "busy",
+                    Some(*until),
+                    None,
+                    Msg::WatchBusy(&time(*until)).to_string(),
+                ),
+                WatchState::Closed(reason) => (
+                    "closed",
+                    None,
+                    Some(reason.clone()),
+                    Msg::WatchClosed(reason).to_string(),
+                ),
+            };
+            if format == Format::Table {
+                println!("{} {} {}", now.format("%H:%M"), location, text);
+            } else {
+                stream.print(&WatchRecord {
+                    room: location.to_string(),
+                    time: now,
+                    state: name,
+                    until,
+                    reason,
+                });
+            }
+        }
+        if let WatchState::Free { until } = state {
+            if !matches!(last, Some(WatchState::Free { .. })) {
+                if alert.bell {
+                    eprint!("\x07");
+                }
+                if let Some(command) = &alert.exec {
+                    run_hook(command, &location, now, until).await;
+                }
+                if alert.once {
+                    return;
+                }
+            }
+        }
+        last = Some(state);
+        tokio::time::sleep(every.to_std().unwrap()).await;
     }
-    if let Some(time) = args.end_time {
-        println!("{}", time);
-        enddatetime = NaiveDateTime::new(start_time.date(), time);
+}
+
+/// Runs the `watch` hook through the shell with the room, the current time
+/// and the end of the free time (`YYYY-MM-DDTHH:MM`) as arguments and waits for it.
+async fn run_hook(command: &str, room: &Location, from: NaiveDateTime, until: NaiveDateTime) {
+    let room = room.to_string();
+    let from = from.format("%Y-%m-%dT%H:%M").to_string();
+    let until = until.format("%Y-%m-%dT%H:%M").to_string();
+    let status = if cfg!(windows) {
+        tokio::process::Command::new("cmd")
+            .arg("/C")
+            .arg(format!("{} {} {} {}", command, room, from, until))
+            .status()
+            .await
+    } else {
+        // The arguments are passed on as "$@", so they do not need to be quoted
+        tokio::process::Command::new("sh")
+            .arg("-c")
+            .arg(format!("{} \"$@\"", command))
+            .args(["sh", &room, &from, &until])
+            .status()
+            .await
+    };
+    match status {
+        Ok(status) if !status.success() => warn!("{}", Msg::HookFailed(&command, &status)),
+        Ok(_) => {}
+        Err(e) => warn!("{}", Msg::HookNotStarted(&command, &e)),
     }
-    if start_time > enddatetime {
-        start_time = enddatetime;
+}
+
+/// Checks the window against the opening hours of the configured room's block.
+/// Prints a note if the window gets trimmed, or the reason if it is rejected.
+/// Returns false if the building is closed during the whole window.
+fn check_opening_hours(
+    config: &Config,
+    start_time: NaiveDateTime,
+    enddatetime: NaiveDateTime,
+    format: Format,
+) -> bool {
+    match opening_hours_note(&load_opening_hours(), config, start_time, enddatetime) {
+        Ok(note) => {
+            if let Some(note) = note {
+                format.note(&note);
+            }
+            true
+        }
+        Err(reason) => {
+            format.note(&Msg::NoRoomsAvailable(&reason).to_string());
+            false
+        }
     }
-    // Query and print nearest available rooms
-    let keys = get_rooms(
+}
+
+/// Loads the opening hours or exits with an error.
+fn load_opening_hours() -> OpeningHours {
+    OpeningHours::load().unwrap_or_else(|e| {
+        eprintln!("{}", Msg::LoadingFailed(&"opening_hours.json", &e));
+        std::process::exit(1);
+    })
+}
+
+/// Trims the window to the opening hours of the configured room's block.
+/// Returns a note if the window gets trimmed, or the reason if it is rejected.
+fn opening_hours_note(
+    opening_hours: &OpeningHours,
+    config: &Config,
+    start_time: NaiveDateTime,
+    enddatetime: NaiveDateTime,
+) -> Result<Option<String>, String> {
+    let (start, end) = opening_hours.trim(Some(config.room.block), start_time, enddatetime)?;
+    if start == start_time && end == enddatetime {
+        return Ok(None);
+    }
+    // Windows over several days are shown with dates
+    let time = |time: NaiveDateTime| {
+        if start.date() == end.date() {
+            time.format("%H:%M").to_string()
+        } else {
+            locale::datetime(time)
+        }
+    };
+    Ok(Some(
+        Msg::WindowTrimmed(&time(start), &time(end), &config.room.block).to_string(),
+    ))
+}
+
+/// Prints the days on which the campus is closed instead of suggesting rooms for them.
+fn print_closed(closed: &[(NaiveDate, String)], format: Format) {
+    for (date, reason) in closed {
+        format.note(&Msg::CampusClosedOn(&locale::date(*date), reason).to_string());
+    }
+}
+
+/// Tells the user which page of the results is shown and how to get the next one.
+fn print_page<T>(paged: &Paged<T>, format: Format) {
+    if paged.number > paged.pages {
+        format.note(&Msg::PageEmpty(&paged.number, &paged.pages).to_string());
+    } else if paged.number < paged.pages {
+        format.note(
+            &Msg::PageOf(
+                &paged.number,
+                &paged.pages,
+                &paged.total,
+                &(paged.number + 1),
+            )
+            .to_string(),
+        );
+    }
+}
+
+/// Runs a recurring query for the weekday given on the command line and prints,
+/// for each candidate room, how many occurrences it is free for and the dates it is taken.
+/// The series starts at the given date (or today) and runs for `--weeks` weeks (default 8) or `--until` a date.
+#[allow(clippy::too_many_arguments)]
+async fn find_recurring(
+    args: &FindArgs,
+    weekday: Weekday,
+    config: &Config,
+    origins: &Origins,
+    reload: bool,
+    filter: &FilterSet,
+    format: Format,
+    page: &Page,
+) {
+    let Some(start) = args
+        .time
+        .map(TimeInput::time)
+        .or(args.at.map(|at| at.time()))
+    else {
+        eprintln!("{}", Msg::RecurringNeedsTimes);
+        std::process::exit(1);
+    };
+    let end = match (
+        args.end_time
+            .map(TimeInput::time)
+            .or(args.to.map(|to| to.time())),
+        args.length,
+    ) {
+        (Some(end), _) => end,
+        // Every occurrence has to end on its own day
+        (None, Some(length)) => match start.overflowing_add_signed(length) {
+            (end, 0) => end,
+            _ => {
+                eprintln!(
+                    "{}",
+                    Msg::InvalidDuration(&format!("{}m", length.num_minutes()))
+                );
+                std::process::exit(1);
+            }
+        },
+        (None, None) => {
+            eprintln!("{}", Msg::RecurringNeedsTimes);
+            std::process::exit(1);
+        }
+    };
+    if end <= start {
+        eprintln!(
+            "{}",
+            Msg::InvalidWindow(&end.format("%H:%M"), &start.format("%H:%M"))
+        );
+        std::process::exit(1);
+    }
+    let from = args
+        .date
+        .or(args.at.map(|at| at.date()))
+        .unwrap_or_else(|| Local::now().date_naive());
+    let weeks = args
+        .weeks
+        .or(if args.until.is_none() { Some(8) } else { None });
+    let recurrence =
+        Recurrence::new(weekday, start, end, from, weeks, args.until).unwrap_or_else(|| {
+            eprintln!("{}", Msg::RecurringNeedsEnd);
+            std::process::exit(1);
+        });
+
+    // Check every occurrence before searching, like a single window
+    let closures = load_closures();
+    let occurrences = recurrence.occurrences();
+    let closed: Vec<(NaiveDate, String)> = occurrences
+        .iter()
+        .filter_map(|(start, _)| {
+            closures
+                .closed_reason(start.date())
+                .map(|reason| (start.date(), reason))
+        })
+        .collect();
+    if !closed.is_empty() {
+        print_closed(&closed, format);
+    }
+    let total = occurrences.len() - closed.len();
+    let opening_hours = load_opening_hours();
+    let checked: Vec<(NaiveDate, Result<Option<String>, String>)> = recurrence
+        .open_occurrences(&closures)
+        .iter()
+        .map(|(start, end)| {
+            (
+                start.date(),
+                opening_hours_note(&opening_hours, config, *start, *end),
+            )
+        })
+        .collect();
+    // Notes that only apply to some occurrences are given with their dates
+    let mut notes: Vec<(String, Vec<String>)> = Vec::new();
+    for (date, check) in &checked {
+        let note = match check {
+            Ok(None) => continue,
+            Ok(Some(note)) => note.clone(),
+            Err(reason) => Msg::NoRoomsAvailable(reason).to_string(),
+        };
+        match notes.iter_mut().find(|(other, _)| *other == note) {
+            Some((_, dates)) => dates.push(locale::date(*date)),
+            None => notes.push((note, vec![locale::date(*date)])),
+        }
+    }
+    for (note, dates) in &notes {
+        if dates.len() == checked.len() {
+            format.note(note);
+        } else {
+            format.note(&Msg::OnDates(&dates.join(", "), note).to_string());
+        }
+    }
+    if checked.iter().all(|(_, check)| check.is_err()) {
+        print_records::<RecurringRecord>(format, &[]);
+        return;
+    }
+
+    let mut paged = get_recurring_rooms(reload, origins, page, &recurrence, filter)
+        .await
+        .unwrap_or_else(|e| exit_search_failed(e));
+    let rooms = std::mem::take(&mut paged.items);
+    if format != Format::Table {
+        let records: Vec<RecurringRecord> = rooms
+            .into_iter()
+            .map(|room| RecurringRecord {
+                room: room.room.to_string(),
+                distance: known_distance(room.distance),
+                free: room.free,
+                total,
+                taken: room.taken,
+            })
+            .collect();
+        print_records(format, &records);
+        print_page(&paged, format);
+        return;
+    }
+    println!(
+        "{}",
+        Msg::RecurringHeader(
+            &format!(
+                "{} {}-{}",
+                locale::weekday(weekday),
+                start.format("%H:%M"),
+                end.format("%H:%M")
+            ),
+            &locale::date(recurrence.first_date),
+            &locale::date(recurrence.last_date),
+            &origin_names(origins)
+        )
+    );
+    for room in rooms {
+        let taken: Vec<String> = room.taken.iter().map(|date| locale::date(*date)).collect();
+        if taken.is_empty() {
+            println!(
+                "{}",
+                Msg::RecurringRoom(
+                    &room.room,
+                    &room.free,
+                    &total,
+                    &format_distance(room.distance)
+                )
+            );
+        } else {
+            println!(
+                "{}",
+                Msg::RecurringRoomTaken(
+                    &room.room,
+                    &room.free,
+                    &total,
+                    &format_distance(room.distance),
+                    &taken.join(", ")
+                )
+            );
+        }
+    }
+    print_page(&paged, format);
+}
+
+/// Runs a partial availability query for the window and prints the rooms ranked by
+/// the share of the window they are free, together with the booked sub-intervals.
+async fn find_partial(
+    origins: &Origins,
+    reload: bool,
+    start_time: NaiveDateTime,
+    enddatetime: NaiveDateTime,
+    filter: &FilterSet,
+    format: Format,
+    page: &Page,
+) {
+    let mut paged = get_partial_rooms(reload, origins, page, start_time, enddatetime, filter)
+        .await
+        .unwrap_or_else(|e| exit_search_failed(e));
+    let rooms = std::mem::take(&mut paged.items);
+    if format != Format::Table {
+        let records: Vec<PartialRecord> = rooms
+            .into_iter()
+            .map(|room| PartialRecord {
+                room: room.room.to_string(),
+                distance: known_distance(room.distance),
+                free_percent: room.free_fraction * 100.0,
+                taken: room
+                    .taken
+                    .into_iter()
+                    .map(|(start, end)| Window { start, end })
+                    .collect(),
+                window: Window {
+                    start: start_time,
+                    end: enddatetime,
+                },
+            })
+            .collect();
+        print_records(format, &records);
+        print_page(&paged, format);
+        return;
+    }
+    println!("{}", Msg::MostlyFreeRooms(&origin_names(origins)));
+    for room in rooms {
+        let taken: Vec<String> = room
+            .taken
+            .iter()
+            .map(|(start, end)| format!("{}-{}", start.format("%H:%M"), end.format("%H:%M")))
+            .collect();
+        if taken.is_empty() {
+            println!(
+                "{}",
+                Msg::PartialRoom(
+                    &room.room,
+                    &(room.free_fraction * 100.0),
+                    &format_distance(room.distance)
+                )
+            );
+        } else {
+            println!(
+                "{}",
+                Msg::PartialRoomTaken(
+                    &room.room,
+                    &(room.free_fraction * 100.0),
+                    &format_distance(room.distance),
+                    &taken.join(", ")
+                )
+            );
+        }
+    }
+    print_page(&paged, format);
+}
+
+/// Finds groups of free rooms next to each other and prints them, nearest first.
+#[allow(clippy::too_many_arguments)]
+async fn find_clusters(
+    origins: &Origins,
+    reload: bool,
+    query: &ClusterQuery,
+    start_time: NaiveDateTime,
+    enddatetime: NaiveDateTime,
+    filter: &FilterSet,
+    format: Format,
+    page: &Page,
+) {
+    let mut paged = get_clusters(
         reload,
-        &config.room.to_string(),
-        10,
+        origins,
+        page,
+        query,
         start_time,
         enddatetime,
+        filter,
     )
     .await
-    .expect("Fehler bei get_rooms");
-    println!("neares rooms from {} are: ", config.room.to_string());
-    for (roomname, distance) in keys {
-        println!("{} (distance: {})", roomname, distance);
+    .unwrap_or_else(|e| exit_search_failed(e));
+    let clusters = std::mem::take(&mut paged.items);
+    if format != Format::Table {
+        let records: Vec<ClusterRecord> = clusters
+            .into_iter()
+            .map(|cluster| ClusterRecord {
+                rooms: cluster.rooms.iter().map(|room| room.to_string()).collect(),
+                distance: known_distance(cluster.distance),
+                span: cluster.span,
+                window: Window {
+                    start: start_time,
+                    end: enddatetime,
+                },
+            })
+            .collect();
+        print_records(format, &records);
+        print_page(&paged, format);
+        return;
+    }
+    if paged.total == 0 {
+        println!("{}", Msg::NoClusters(&query.size, &query.max_gap));
+        return;
+    }
+    println!(
+        "{}",
+        Msg::ClustersHeader(&query.size, &origin_names(origins))
+    );
+    for cluster in clusters {
+        let rooms: Vec<String> = cluster.rooms.iter().map(|room| room.to_string()).collect();
+        println!(
+            "{}",
+            Msg::Cluster(
+                &rooms.join(", "),
+                &format_distance(cluster.distance),
+                &cluster.span
+            )
+        );
+    }
+    print_page(&paged, format);
+}
+
+/// Computes utilization statistics for the date range and prints a heatmap plus the
+/// occupancy per block, floor and room (quietest rooms first).
+/// If `csv` is given, the same data is written to that file.
+async fn show_stats(
+    reload: bool,
+    from: NaiveDate,
+    to: NaiveDate,
+    first_hour: u32,
+    last_hour: u32,
+    csv: Option<&PathBuf>,
+    format: Format,
+) {
+    let stats = get_stats(reload, from, to, first_hour, last_hour)
+        .await
+        .unwrap_or_else(|e| exit_search_failed(e));
+    if let Some(path) = csv {
+        fs::write(path, stats.to_csv()).unwrap_or_else(|e| {
+            eprintln!("{}", Msg::WritingFileFailed(&path.display(), &e));
+            std::process::exit(1);
+        });
+    }
+    if format != Format::Table {
+        let mut records: Vec<StatRecord> = Vec::new();
+        let mut add = |category, name: String, occupancy: &Occupancy| {
+            records.push(StatRecord {
+                category,
+                name,
+                occupancy_percent: occupancy.percent(),
+            })
+        };
+        for (room, occupancy) in &stats.rooms {
+            add("room", room.to_string(), occupancy);
+        }
+        for (block, occupancy) in &stats.blocks {
+            add("block", block.to_string(), occupancy);
+        }
+        for (floor, occupancy) in &stats.floors {
+            add("floor", floor.clone(), occupancy);
+        }
+        for ((weekday, hour), occupancy) in &stats.weekday_hours {
+            let weekday = Weekday::try_from(*weekday as u8).unwrap_or(Weekday::Mon);
+            add(
+                "weekday_hour",
+                format!("{} {:02}", weekday, hour),
+                occupancy,
+            );
+        }
+        print_records(format, &records);
+        return;
+    }
+    println!(
+        "{}",
+        Msg::OccupancyHeader(
+            &locale::date(from),
+            &locale::date(to),
+            &first_hour,
+            &last_hour
+        )
+    );
+    print!("{}", stats.heatmap());
+
+    println!("\n{}", Msg::PerBlock);
+    for (block, occupancy) in &stats.blocks {
+        println!("{} {:5.1}%", block, occupancy.percent());
+    }
+    println!("\n{}", Msg::PerFloor);
+    for (floor, occupancy) in &stats.floors {
+        println!("{} {:5.1}%", floor, occupancy.percent());
+    }
+    println!("\n{}", Msg::PerRoom);
+    let mut rooms: Vec<_> = stats.rooms.iter().collect();
+    rooms.sort_by(|a, b| a.1.percent().total_cmp(&b.1.percent()));
+    for (room, occupancy) in rooms {
+        println!("{} {:5.1}%", room, occupancy.percent());
+    }
+
+    if let Some(path) = csv {
+        println!("\n{}", Msg::StatsWritten(&path.display()));
+    }
+}
+
+/// Prints all known rooms with their catalogue metadata;
+/// rooms removed by the filters are marked with the reason.
+async fn list_rooms(reload: bool, filter: &FilterSet, format: Format) {
+    let rooms = get_room_list(reload, filter)
+        .await
+        .unwrap_or_else(|e| exit_search_failed(e));
+    if format != Format::Table {
+        let records: Vec<RoomRecord> = rooms
+            .into_iter()
+            .map(|entry| {
+                let info = entry.info.unwrap_or_default();
+                RoomRecord {
+                    room: entry.room.to_string(),
+                    room_type: info.room_type.map(|room_type| room_type.to_string()),
+                    capacity: info.capacity,
+                    equipment: info.equipment,
+                    accessible: info.accessible,
+                    filtered: entry.filtered,
+                }
+            })
+            .collect();
+        print_records(format, &records);
+        return;
+    }
+    for entry in rooms {
+        let mut details = Vec::new();
+        if let Some(info) = &entry.info {
+            if let Some(room_type) = info.room_type {
+                details.push(Msg::RoomTypeName(room_type).to_string());
+            }
+            if let Some(capacity) = info.capacity {
+                details.push(Msg::Seats(&capacity).to_string());
+            }
+            if !info.equipment.is_empty() {
+                details.push(info.equipment.join(", "));
+            }
+            if info.accessible {
+                details.push(Msg::Accessible.to_string());
+            }
+        }
+        if let Some(reason) = &entry.filtered {
+            details.push(Msg::Filtered(reason).to_string());
+        }
+        if details.is_empty() {
+            println!("{}", entry.room);
+        } else {
+            println!("{} ({})", entry.room, details.join("; "));
+        }
     }
 }
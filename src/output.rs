@@ -0,0 +1,418 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use clap::ValueEnum;
+use dhbw_roomfinder::stats::csv_field;
+use serde::Serialize;
+use serde_json::Value;
+use std::io::{self, Write};
+
+/// Output format of the commands. `table` is the human readable text output,
+/// the others print one record per result with a stable schema (see README).
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Format {
+    #[default]
+    Table,
+    Json,
+    Ndjson,
+    Csv,
+}
+
+impl Format {
+    /// Prints a note for the user, on stdout for tables and on stderr otherwise,
+    /// so machine readable output only contains records.
+    pub fn note(&self, text: &str) {
+        if *self == Format::Table {
+            println!("{}", text);
+        } else {
+            eprintln!("{}", text);
+        }
+    }
+}
+
+/// Start and end of a search window.
+#[derive(Serialize, Debug, Clone, Copy, Default)]
+pub struct Window {
+    pub start: NaiveDateTime,
+    pub end: NaiveDateTime,
+}
+
+/// Returns the distance of a record, None for rooms without a known way from the origin.
+pub fn known_distance(distance: u32) -> Option<u32> {
+    (distance != u32::MAX).then_some(distance)
+}
+
+/// A free room of a `find` search.
+/// `distance` is null for rooms that cannot be reached,
+/// `distances` holds the distance from each origin room (null if it cannot be reached).
+#[derive(Serialize, Debug, Default)]
+pub struct FindRecord {
+    pub room: String,
+    pub distance: Option<u32>,
+    pub distances: serde_json::Map<String, Value>,
+    pub free_until: NaiveDateTime,
+    pub next_event: Option<NaiveDateTime>,
+    pub quietness: f64,
+    pub score: f64,
+    pub window: Window,
+}
+
+impl FindRecord {
+    /// Returns an empty record with a distance for each origin room,
+    /// so the CSV header has the same columns with and without results.
+    pub fn empty(origins: &[String]) -> Self {
+        FindRecord {
+            distances: origins
+                .iter()
+                .map(|origin| (origin.clone(), Value::Null))
+                .collect(),
+            ..FindRecord::default()
+        }
+    }
+}
+
+/// A room of a partial availability search with the booked parts of the window.
+/// `distance` is null for rooms that cannot be reached, as in the other records.
+#[derive(Serialize, Debug, Default)]
+pub struct PartialRecord {
+    pub room: String,
+    pub distance: Option<u32>,
+    pub free_percent: f64,
+    pub taken: Vec<Window>,
+    pub window: Window,
+}
+
+/// A room of a recurring search with the number of free occurrences and the dates it is taken.
+#[derive(Serialize, Debug, Default)]
+pub struct RecurringRecord {
+    pub room: String,
+    pub distance: Option<u32>,
+    pub free: usize,
+    pub total: usize,
+    pub taken: Vec<NaiveDate>,
+}
+
+/// A group of free rooms next to each other.
+#[derive(Serialize, Debug, Default)]
+pub struct ClusterRecord {
+    pub rooms: Vec<String>,
+    pub distance: Option<u32>,
+    pub span: u32,
+    pub window: Window,
+}
+
+/// A line of a room timetable: an event, a free gap or a closed day.
+#[derive(Serialize, Debug, Default)]
+pub struct TimetableRecord {
+    pub room: String,
+    pub date: NaiveDate,
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+    pub start: Option<NaiveDateTime>,
+    pub end: Option<NaiveDateTime>,
+    pub title: Option<String>,
+    pub courses: Vec<String>,
+    pub status: Option<String>,
+}
+
+/// A free time slot of a room.
+#[derive(Serialize, Debug, Default)]
+pub struct SlotRecord {
+    pub room: String,
+    pub distance: Option<u32>,
+    pub start: NaiveDateTime,
+    pub end: NaiveDateTime,
+}
+
+/// A known room with its catalogue metadata and the reason it is filtered out, if any.
+#[derive(Serialize, Debug, Default)]
+pub struct RoomRecord {
+    pub room: String,
+    #[serde(rename = "type")]
+    pub room_type: Option<String>,
+    pub capacity: Option<u32>,
+    pub equipment: Vec<String>,
+    pub accessible: bool,
+    pub filtered: Option<String>,
+}
+
+/// The occupancy of a room, block, floor or weekday/hour cell.
+#[derive(Serialize, Debug, Default)]
+pub struct StatRecord {
+    pub category: &'static str,
+    pub name: String,
+    pub occupancy_percent: f64,
+}
+
+/// A state change of a watched room: `free`, `short` (free, but not for long enough),
+/// `busy` or `closed` with the reason. `until` is null for closed rooms.
+#[derive(Serialize, Debug)]
+pub struct WatchRecord {
+    pub room: String,
+    pub time: NaiveDateTime,
+    pub state: &'static str,
+    pub until: Option<NaiveDateTime>,
+    pub reason: Option<String>,
+}
+
+/// Prints records one at a time as they come, for commands that keep running.
+/// JSON and NDJSON print one object per line, CSV prints the header before the first record.
+pub struct RecordStream {
+    format: Format,
+    header_written: bool,
+}
+
+impl RecordStream {
+    pub fn new(format: Format) -> Self {
+        RecordStream {
+            format,
+            header_written: false,
+        }
+    }
+
+    /// Prints a single record and flushes stdout. Does nothing for tables.
+    pub fn print<T: Serialize>(&mut self, record: &T) {
+        let mut out = io::stdout().lock();
+        let _ = match self.format {
+            Format::Table => Ok(()),
+            Format::Json | Format::Ndjson => {
+                write_records(&mut out, Format::Ndjson, &[record], None)
+            }
+            Format::Csv => {
+                let header = (!self.header_written).then_some(&record);
+                write_records(&mut out, Format::Csv, &[record], header)
+            }
+        };
+        self.header_written = true;
+        let _ = out.flush();
+    }
+}
+
+/// Prints the records as a JSON array, one JSON object per line (NDJSON)
+/// or as CSV with a header line, which is also printed if there are no records.
+/// Does nothing for tables.
+/// Stops quietly if stdout is closed, e.g. when piped into `head`.
+pub fn print_records<T: Serialize + Default>(format: Format, records: &[T]) {
+    print_records_like(format, records, &T::default());
+}
+
+/// Prints the records like `print_records`, with the CSV header taken from `empty`
+/// if there are no records (e.g. with columns that depend on the search).
+pub fn print_records_like<T: Serialize>(format: Format, records: &[T], empty: &T) {
+    let _ = write_records(&mut io::stdout().lock(), format, records, Some(empty));
+}
+
+/// Writes the records in the given format, see `print_records`.
+/// The CSV header is only written if `header` is given. Its columns are those of the first record,
+/// or those of `header` if there are no records.
+fn write_records<T: Serialize>(
+    out: &mut impl Write,
+    format: Format,
+    records: &[T],
+    header: Option<&T>,
+) -> io::Result<()> {
+    match format {
+        Format::Table => {}
+        Format::Json => writeln!(
+            out,
+            "{}",
+            serde_json::to_string_pretty(records).map_err(io::Error::other)?
+        )?,
+        Format::Ndjson => {
+            for record in records {
+                writeln!(
+                    out,
+                    "{}",
+                    serde_json::to_string(record).map_err(io::Error::other)?
+                )?;
+            }
+        }
+        Format::Csv => {
+            if let Some(empty) = header {
+                let first = records.first().unwrap_or(empty);
+                let keys: Vec<String> = csv_row(first)?.into_iter().map(|(key, _)| key).collect();
+                writeln!(out, "{}", keys.join(","))?;
+            }
+            for record in records {
+                let fields: Vec<String> = csv_row(record)?
+                    .iter()
+                    .map(|(_, field)| csv_field(field))
+                    .collect();
+                writeln!(out, "{}", fields.join(","))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Flattens a record into its CSV columns and fields, see `flatten`.
+fn csv_row<T: Serialize>(record: &T) -> io::Result<Vec<(String, String)>> {
+    let mut row = Vec::new();
+    flatten(
+        "",
+        &serde_json::to_value(record).map_err(io::Error::other)?,
+        &mut row,
+    );
+    Ok(row)
+}
+
+/// Prints a single JSON value, e.g. a configuration value.
+/// CSV lists every value with its dotted key.
+pub fn print_value(format: Format, value: &Value) {
+    match format {
+        Format::Table | Format::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(value).expect("failed to format value")
+        ),
+        Format::Ndjson => println!(
+            "{}",
+            serde_json::to_string(value).expect("failed to format value")
+        ),
+        Format::Csv => {
+            let mut rows = Vec::new();
+            flatten("", value, &mut rows);
+            println!("key,value");
+            for (key, field) in rows {
+                println!("{},{}", csv_field(&key), csv_field(&field));
+            }
+        }
+    }
+}
+
+/// Flattens a JSON value into CSV columns. Nested objects are joined with `_`
+/// (e.g. `window_start`), lists become one field with `;` between the items
+/// and objects inside lists are written as their values joined with `/` (e.g. `start/end`).
+fn flatten(prefix: &str, value: &Value, row: &mut Vec<(String, String)>) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                let key = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}_{}", prefix, key)
+                };
+                flatten(&key, value, row);
+            }
+        }
+        value => row.push((prefix.to_owned(), scalar(value))),
+    }
+}
+
+/// Formats a JSON value as a single CSV field.
+fn scalar(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(text) => text.clone(),
+        Value::Array(items) => items.iter().map(scalar).collect::<Vec<_>>().join(";"),
+        Value::Object(map) => map.values().map(scalar).collect::<Vec<_>>().join("/"),
+        value => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(day: u32, hour: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 10, day)
+            .unwrap()
+            .and_hms_opt(hour, 0, 0)
+            .unwrap()
+    }
+
+    fn csv<T: Serialize>(records: &[T], header: Option<&T>) -> String {
+        let mut out = Vec::new();
+        write_records(&mut out, Format::Csv, records, header).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    fn find_record(room: &str, distances: &[(&str, Option<u32>)]) -> FindRecord {
+        FindRecord {
+            room: room.to_owned(),
+            distance: distances.iter().map(|(_, distance)| *distance).sum(),
+            distances: distances
+                .iter()
+                .map(|(origin, distance)| (origin.to_string(), (*distance).into()))
+                .collect(),
+            free_until: at(19, 12),
+            next_event: None,
+            quietness: 1.0,
+            score: 0.5,
+            window: Window {
+                start: at(19, 10),
+                end: at(19, 11),
+            },
+        }
+    }
+
+    #[test]
+    fn expands_the_distance_of_each_origin_into_a_column() {
+        let origins = ["A266".to_owned(), "B301".to_owned()];
+        let records = [
+            find_record("A267", &[("A266", Some(1)), ("B301", Some(1034))]),
+            find_record("C101", &[("A266", Some(2165)), ("B301", None)]),
+        ];
+        assert_eq!(
+            csv(&records, Some(&FindRecord::empty(&origins))),
+            "room,distance,distances_A266,distances_B301,free_until,next_event,quietness,score,\
+             window_start,window_end\n\
+             A267,1035,1,1034,2026-10-19T12:00:00,,1.0,0.5,2026-10-19T10:00:00,2026-10-19T11:00:00\n\
+             C101,,2165,,2026-10-19T12:00:00,,1.0,0.5,2026-10-19T10:00:00,2026-10-19T11:00:00\n"
+        );
+    }
+
+    #[test]
+    fn writes_the_header_without_records() {
+        let origins = ["A266".to_owned(), "B301".to_owned()];
+        assert_eq!(
+            csv(&[], Some(&FindRecord::empty(&origins))),
+            "room,distance,distances_A266,distances_B301,free_until,next_event,quietness,score,\
+             window_start,window_end\n"
+        );
+        assert_eq!(
+            csv::<SlotRecord>(&[], Some(&SlotRecord::default())),
+            "room,distance,start,end\n"
+        );
+        assert_eq!(csv::<SlotRecord>(&[], None), "");
+    }
+
+    #[test]
+    fn joins_lists_and_quotes_fields() {
+        let record = RecurringRecord {
+            room: "A266".to_owned(),
+            distance: Some(0),
+            free: 2,
+            total: 4,
+            taken: vec![at(20, 0).date(), at(27, 0).date()],
+        };
+        let room = RoomRecord {
+            room: "Audimax, Hörsaal".to_owned(),
+            equipment: vec!["beamer".to_owned(), "whiteboard".to_owned()],
+            ..RoomRecord::default()
+        };
+        assert_eq!(
+            csv(&[record], Some(&RecurringRecord::default())),
+            "room,distance,free,total,taken\nA266,0,2,4,2026-10-20;2026-10-27\n"
+        );
+        assert_eq!(
+            csv(&[room], None),
+            "\"Audimax, Hörsaal\",,,beamer;whiteboard,false,\n"
+        );
+    }
+
+    #[test]
+    fn writes_one_json_object_per_line() {
+        let records = [
+            find_record("A267", &[("A266", Some(1))]),
+            find_record("C101", &[("A266", None)]),
+        ];
+        let mut out = Vec::new();
+        write_records(&mut out, Format::Ndjson, &records, None).unwrap();
+        let lines: Vec<Value> = String::from_utf8(out)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[1]["distance"], Value::Null);
+        assert_eq!(lines[1]["distances"]["A266"], Value::Null);
+    }
+}
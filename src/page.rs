@@ -0,0 +1,93 @@
+/// A page of a result list: `size` results per page (all results if None),
+/// `number` counts from 1.
+#[derive(Debug, Clone, Copy)]
+pub struct Page {
+    pub number: usize,
+    pub size: Option<usize>,
+}
+
+impl Default for Page {
+    fn default() -> Self {
+        Page {
+            number: 1,
+            size: Some(10),
+        }
+    }
+}
+
+/// The results on one page together with the total number of results and pages.
+#[derive(Debug, Clone)]
+pub struct Paged<T> {
+    pub items: Vec<T>,
+    pub total: usize,
+    pub number: usize,
+    pub pages: usize,
+}
+
+impl Page {
+    /// Takes the results of this page from a sorted list.
+    /// Pages past the end are empty.
+    pub fn apply<T>(&self, items: Vec<T>) -> Paged<T> {
+        let total = items.len();
+        let Some(size) = self.size.filter(|size| *size > 0) else {
+            return Paged {
+                items,
+                total,
+                number: 1,
+                pages: 1,
+            };
+        };
+        let items = items
+            .into_iter()
+            .skip(size.saturating_mul(self.number.saturating_sub(1)))
+            .take(size)
+            .collect();
+        Paged {
+            items,
+            total,
+            number: self.number,
+            pages: total.div_ceil(size).max(1),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(number: usize, size: Option<usize>) -> Page {
+        Page { number, size }
+    }
+
+    #[test]
+    fn takes_the_results_of_the_page() {
+        let paged = page(2, Some(3)).apply((1..=8).collect());
+        assert_eq!(paged.items, vec![4, 5, 6]);
+        assert_eq!((paged.total, paged.number, paged.pages), (8, 2, 3));
+
+        let last = page(3, Some(3)).apply((1..=8).collect());
+        assert_eq!(last.items, vec![7, 8]);
+    }
+
+    #[test]
+    fn pages_past_the_end_are_empty() {
+        let paged = page(4, Some(3)).apply((1..=8).collect());
+        assert!(paged.items.is_empty());
+        assert_eq!((paged.total, paged.pages), (8, 3));
+    }
+
+    #[test]
+    fn without_a_size_all_results_are_one_page() {
+        for size in [None, Some(0)] {
+            let paged = page(2, size).apply((1..=8).collect::<Vec<_>>());
+            assert_eq!(paged.items.len(), 8);
+            assert_eq!((paged.number, paged.pages), (1, 1));
+        }
+    }
+
+    #[test]
+    fn empty_results_have_one_page() {
+        let paged = Page::default().apply(Vec::<u32>::new());
+        assert_eq!((paged.total, paged.pages), (0, 1));
+    }
+}
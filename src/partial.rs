@@ -0,0 +1,134 @@
+use chrono::NaiveDateTime;
+
+use crate::free::{self, Booking};
+use crate::room::Location;
+
+/// Result of a partial availability query for a single room.
+/// `free_fraction` is the share of the window (0.0 to 1.0) in which the room is not booked,
+/// `taken` holds the booked sub-intervals of the window.
+#[derive(Debug, Clone)]
+pub struct PartialRoom {
+    pub room: Location,
+    pub distance: u32,
+    pub free_fraction: f64,
+    pub taken: Vec<(NaiveDateTime, NaiveDateTime)>,
+}
+
+/// Returns the taken parts of the window, sorted by start: the bookings
+/// and the time outside of the opening hours.
+/// - `open`: The window trimmed to the opening hours, None if it is closed the whole time.
+pub fn taken_in_window(
+    bookings: &[Booking],
+    open: Option<(NaiveDateTime, NaiveDateTime)>,
+    start_time: NaiveDateTime,
+    enddatetime: NaiveDateTime,
+) -> Vec<(NaiveDateTime, NaiveDateTime)> {
+    let mut taken = match open {
+        Some((start, end)) => {
+            let mut taken = free::taken_intervals(bookings, start, end);
+            taken.insert(0, (start_time, start));
+            taken.push((end, enddatetime));
+            taken
+        }
+        None => vec![(start_time, enddatetime)],
+    };
+    taken.retain(|(start, end)| start < end);
+    taken
+}
+
+/// Calculates the share of the window that is not covered by the given taken intervals.
+/// An empty window counts as completely free unless it is taken.
+pub fn free_fraction(
+    taken: &[(NaiveDateTime, NaiveDateTime)],
+    start_time: NaiveDateTime,
+    enddatetime: NaiveDateTime,
+) -> f64 {
+    let window = (enddatetime - start_time).num_seconds();
+    if window <= 0 {
+        return if taken.is_empty() { 1.0 } else { 0.0 };
+    }
+    let taken: i64 = taken
+        .iter()
+        .map(|(start, end)| (*end - *start).num_seconds())
+        .sum();
+    1.0 - taken as f64 / window as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn at(hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 10, 20)
+            .unwrap()
+            .and_hms_opt(hour, minute, 0)
+            .unwrap()
+    }
+
+    fn bookings(bookings: &[(NaiveDateTime, NaiveDateTime)]) -> Vec<Booking> {
+        bookings
+            .iter()
+            .map(|(start, end)| Booking {
+                start: *start,
+                end: *end,
+            })
+            .collect()
+    }
+
+    fn fraction(booked: &[(NaiveDateTime, NaiveDateTime)]) -> f64 {
+        let window = Some((at(10, 0), at(12, 0)));
+        let taken = taken_in_window(&bookings(booked), window, at(10, 0), at(12, 0));
+        free_fraction(&taken, at(10, 0), at(12, 0))
+    }
+
+    #[test]
+    fn counts_only_the_part_inside_the_window() {
+        assert_eq!(fraction(&[]), 1.0);
+        // Bookings ending at the start or starting at the end do not take anything
+        assert_eq!(
+            fraction(&[(at(8, 0), at(10, 0)), (at(12, 0), at(14, 0))]),
+            1.0
+        );
+        // Bookings across the edges are clipped to the window
+        assert_eq!(fraction(&[(at(9, 0), at(10, 30))]), 0.75);
+        assert_eq!(fraction(&[(at(11, 30), at(13, 0))]), 0.75);
+        assert_eq!(fraction(&[(at(9, 0), at(13, 0))]), 0.0);
+        // Overlapping bookings are only counted once
+        assert_eq!(
+            fraction(&[(at(10, 0), at(11, 0)), (at(10, 30), at(11, 0))]),
+            0.5
+        );
+    }
+
+    #[test]
+    fn empty_windows_are_free_unless_taken() {
+        assert_eq!(free_fraction(&[], at(10, 0), at(10, 0)), 1.0);
+        assert_eq!(
+            free_fraction(&[(at(10, 0), at(10, 0))], at(10, 0), at(10, 0)),
+            0.0
+        );
+    }
+
+    #[test]
+    fn time_outside_of_the_opening_hours_is_taken() {
+        // Opening hours from 08:00 to 20:00
+        let booked = bookings(&[(at(8, 0), at(8, 30)), (at(19, 0), at(21, 0))]);
+        let taken = taken_in_window(&booked, Some((at(8, 0), at(20, 0))), at(7, 0), at(21, 0));
+        assert_eq!(
+            taken,
+            [
+                (at(7, 0), at(8, 0)),
+                (at(8, 0), at(8, 30)),
+                (at(19, 0), at(20, 0)),
+                (at(20, 0), at(21, 0))
+            ]
+        );
+        assert_eq!(free_fraction(&taken, at(7, 0), at(21, 0)), 0.75);
+        // Nothing is added if the window is within the opening hours
+        let taken = taken_in_window(&[], Some((at(9, 0), at(10, 0))), at(9, 0), at(10, 0));
+        assert!(taken.is_empty());
+        let taken = taken_in_window(&booked, None, at(7, 0), at(21, 0));
+        assert_eq!(taken, [(at(7, 0), at(21, 0))]);
+    }
+}
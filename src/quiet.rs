@@ -0,0 +1,53 @@
+use std::collections::HashMap;
+
+use crate::room::Location;
+use crate::topology::Topology;
+
+/// Returns the rooms next to the given one.
+/// Rooms with neighbours listed in the topology use that list,
+/// all other rooms have the rooms on the same floor with a room number at most two apart
+/// as neighbours (e.g. A264 and A268 for A266).
+pub fn neighbours<'a>(
+    location: &Location,
+    rooms: &'a [Location],
+    topology: Option<&Topology>,
+) -> Vec<&'a Location> {
+    if let Some(listed) = topology.and_then(|topology| topology.listed_neighbours(location)) {
+        return rooms.iter().filter(|room| listed.contains(room)).collect();
+    }
+    rooms
+        .iter()
+        .filter(|other| match (location.room_id(), other.room_id()) {
+            (Some(room), Some(other)) => {
+                room != other
+                    && room.block == other.block
+                    && room.floor == other.floor
+                    && room.number.abs_diff(other.number) <= 2
+            }
+            _ => false,
+        })
+        .collect()
+}
+
+/// Calculates how quiet a room is during a window from the occupancy of its neighbours,
+/// between 0.0 (all neighbours booked for the whole window) and 1.0 (all neighbours free).
+/// Rooms without neighbours count as quiet.
+/// - `rooms`: All known rooms
+/// - `occupancy`: Share of the window each room is booked
+/// - `topology`: The campus topology with optional neighbour lists
+pub fn quietness(
+    location: &Location,
+    rooms: &[Location],
+    occupancy: &HashMap<Location, f64>,
+    topology: Option<&Topology>,
+) -> f64 {
+    let neighbours = neighbours(location, rooms, topology);
+    if neighbours.is_empty() {
+        return 1.0;
+    }
+    let busy: f64 = neighbours
+        .iter()
+        .map(|neighbour| occupancy.get(*neighbour).copied().unwrap_or(0.0))
+        .sum();
+    1.0 - busy / neighbours.len() as f64
+}
@@ -0,0 +1,419 @@
+use crate::catalogue::Catalogue;
+use crate::locale::Msg;
+use crate::room::RoomId;
+use crate::FreeRoom;
+use chrono::NaiveDateTime;
+
+/// Everything a ranking strategy may need besides the room itself.
+pub struct RankingContext<'a> {
+    pub origins: &'a [RoomId],
+    pub start: NaiveDateTime,
+    pub catalogue: &'a Catalogue,
+}
+
+/// A way to rate free rooms, e.g. by distance or by how long they stay free.
+pub trait RankingStrategy: Send + Sync {
+    /// Name of the strategy as used on the command line and in the config.
+    fn name(&self) -> &'static str;
+    /// Returns a value where higher is better, or None if the room cannot be rated.
+    fn value(&self, room: &FreeRoom, context: &RankingContext) -> Option<f64>;
+    /// Describes what the value is based on, e.g. "distance 8".
+    fn detail(&self, room: &FreeRoom, context: &RankingContext) -> String;
+}
+
+/// Prefers rooms close to the reference room.
+pub struct Nearest;
+
+impl RankingStrategy for Nearest {
+    fn name(&self) -> &'static str {
+        "nearest"
+    }
+
+    fn value(&self, room: &FreeRoom, _: &RankingContext) -> Option<f64> {
+        (room.distance != u32::MAX).then(|| -(room.distance as f64))
+    }
+
+    fn detail(&self, room: &FreeRoom, _: &RankingContext) -> String {
+        if room.distance == u32::MAX {
+            Msg::Unreachable.to_string()
+        } else {
+            Msg::Distance(&room.distance).to_string()
+        }
+    }
+}
+
+/// Prefers rooms that stay free the longest after the start of the window.
+pub struct LongestFree;
+
+impl RankingStrategy for LongestFree {
+    fn name(&self) -> &'static str {
+        "longest_free"
+    }
+
+    fn value(&self, room: &FreeRoom, context: &RankingContext) -> Option<f64> {
+        Some((room.free_until - context.start).num_minutes() as f64)
+    }
+
+    fn detail(&self, room: &FreeRoom, _: &RankingContext) -> String {
+        Msg::FreeUntil(&room.free_until.format("%H:%M")).to_string()
+    }
+}
+
+/// Prefers rooms with more seats according to the room catalogue.
+pub struct LargestCapacity;
+
+impl RankingStrategy for LargestCapacity {
+    fn name(&self) -> &'static str {
+        "largest_capacity"
+    }
+
+    fn value(&self, room: &FreeRoom, context: &RankingContext) -> Option<f64> {
+        context
+            .catalogue
+            .get(&room.room)
+            .and_then(|info| info.capacity)
+            .map(f64::from)
+    }
+
+    fn detail(&self, room: &FreeRoom, context: &RankingContext) -> String {
+        match context
+            .catalogue
+            .get(&room.room)
+            .and_then(|info| info.capacity)
+        {
+            Some(capacity) => Msg::Seats(&capacity).to_string(),
+            None => Msg::UnknownCapacity.to_string(),
+        }
+    }
+}
+
+/// Prefers rooms on the floor of the origin rooms, then rooms in the same block.
+/// With several origin rooms, each one counts the same.
+pub struct SameFloor;
+
+impl SameFloor {
+    /// Returns how many origin rooms are on the same floor and in the same block as the room.
+    fn matches(room: &RoomId, context: &RankingContext) -> (usize, usize) {
+        let same_block: Vec<_> = context
+            .origins
+            .iter()
+            .filter(|origin| origin.block == room.block)
+            .collect();
+        let same_floor = same_block
+            .iter()
+            .filter(|origin| origin.floor == room.floor)
+            .count();
+        (same_floor, same_block.len())
+    }
+}
+
+impl RankingStrategy for SameFloor {
+    fn name(&self) -> &'static str {
+        "same_floor"
+    }
+
+    fn value(&self, room: &FreeRoom, context: &RankingContext) -> Option<f64> {
+        let (same_floor, same_block) = SameFloor::matches(room.room.room_id()?, context);
+        Some((same_floor + same_block) as f64)
+    }
+
+    fn detail(&self, room: &FreeRoom, context: &RankingContext) -> String {
+        let Some(id) = room.room.room_id() else {
+            return Msg::OtherBlock.to_string();
+        };
+        let (same_floor, same_block) = SameFloor::matches(id, context);
+        let total = context.origins.len();
+        match (same_floor, same_block) {
+            (_, 0) => Msg::OtherBlock.to_string(),
+            (floor, _) if floor == total => Msg::SameFloor.to_string(),
+            (0, block) if block == total => Msg::SameBlock.to_string(),
+            (floor, block) => Msg::SameFloorFor(&floor, &block, &total).to_string(),
+        }
+    }
+}
+
+/// Prefers rooms whose neighbours are not booked during the window.
+pub struct Quietest;
+
+impl RankingStrategy for Quietest {
+    fn name(&self) -> &'static str {
+        "quietest"
+    }
+
+    fn value(&self, room: &FreeRoom, _: &RankingContext) -> Option<f64> {
+        Some(room.quietness)
+    }
+
+    fn detail(&self, room: &FreeRoom, _: &RankingContext) -> String {
+        Msg::NeighboursBooked(&((1.0 - room.quietness) * 100.0)).to_string()
+    }
+}
+
+/// Returns the built-in strategy with the given name.
+pub fn strategy(name: &str) -> Result<Box<dyn RankingStrategy>, String> {
+    let strategy: Box<dyn RankingStrategy> = match name.to_lowercase().replace('-', "_").as_str() {
+        "nearest" => Box::new(Nearest),
+        "longest_free" => Box::new(LongestFree),
+        "largest_capacity" => Box::new(LargestCapacity),
+        "same_floor" => Box::new(SameFloor),
+        "quietest" => Box::new(Quietest),
+        _ => return Err(Msg::UnknownStrategy(&name).to_string()),
+    };
+    Ok(strategy)
+}
+
+/// Parses a weighted strategy like "nearest=2" or "quietest" (weight 1).
+pub fn parse_weight(src: &str) -> Result<(String, f64), String> {
+    let (name, weight) = match src.split_once('=') {
+        Some((name, weight)) => (
+            name.trim(),
+            weight
+                .trim()
+                .parse::<f64>()
+                .map_err(|_| Msg::InvalidWeight(&src).to_string())?,
+        ),
+        None => (src.trim(), 1.0),
+    };
+    if !weight.is_finite() || weight < 0.0 {
+        return Err(Msg::InvalidWeight(&src).to_string());
+    }
+    strategy(name)?;
+    Ok((name.to_owned(), weight))
+}
+
+/// How a single strategy contributed to the score of a room.
+#[derive(Debug, Clone)]
+pub struct RankReason {
+    pub strategy: &'static str,
+    pub weight: f64,
+    /// Score between 0 (worst of the results) and 1 (best of the results).
+    pub score: f64,
+    pub detail: String,
+}
+
+/// A weighted combination of ranking strategies.
+pub struct Ranking {
+    strategies: Vec<(Box<dyn RankingStrategy>, f64)>,
+}
+
+impl Default for Ranking {
+    /// Ranks by distance only.
+    fn default() -> Self {
+        Ranking {
+            strategies: vec![(Box::new(Nearest), 1.0)],
+        }
+    }
+}
+
+impl Ranking {
+    /// Creates a ranking from strategy names and weights.
+    /// Returns the default ranking if no weights are given.
+    pub fn from_weights<'a>(
+        weights: impl IntoIterator<Item = (&'a str, f64)>,
+    ) -> Result<Self, String> {
+        let strategies = weights
+            .into_iter()
+            .map(|(name, weight)| Ok((strategy(name)?, weight)))
+            .collect::<Result<Vec<_>, String>>()?;
+        if strategies.is_empty() {
+            return Ok(Ranking::default());
+        }
+        Ok(Ranking { strategies })
+    }
+
+    /// Scores all rooms and sorts them by score (descending), then by distance.
+    /// The value of each strategy is scaled between the worst and the best room,
+    /// rooms that cannot be rated by a strategy get 0 for it.
+    pub fn rank(&self, rooms: &mut [FreeRoom], context: &RankingContext) {
+        for room in rooms.iter_mut() {
+            room.score = 0.0;
+            room.reasons.clear();
+        }
+        for (strategy, weight) in &self.strategies {
+            let values: Vec<Option<f64>> = rooms
+                .iter()
+                .map(|room| strategy.value(room, context))
+                .collect();
+            let min = values
+                .iter()
+                .flatten()
+                .copied()
+                .fold(f64::INFINITY, f64::min);
+            let max = values
+                .iter()
+                .flatten()
+                .copied()
+                .fold(f64::NEG_INFINITY, f64::max);
+            for (room, value) in rooms.iter_mut().zip(values) {
+                let score = match value {
+                    Some(_) if max <= min => 1.0,
+                    Some(value) => (value - min) / (max - min),
+                    None => 0.0,
+                };
+                room.score += score * weight;
+                room.reasons.push(RankReason {
+                    strategy: strategy.name(),
+                    weight: *weight,
+                    score,
+                    detail: strategy.detail(room, context),
+                });
+            }
+        }
+        rooms.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then(a.distance.cmp(&b.distance))
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::catalogue::RoomInfo;
+    use crate::room::Location;
+    use chrono::NaiveDate;
+
+    fn at(hour: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 10, 19)
+            .unwrap()
+            .and_hms_opt(hour, 0, 0)
+            .unwrap()
+    }
+
+    fn free_room(name: &str, distance: u32, free_until: u32, quietness: f64) -> FreeRoom {
+        FreeRoom {
+            room: Location::parse(name).unwrap(),
+            distance,
+            distances: vec![distance],
+            free_until: at(free_until),
+            next_event: None,
+            quietness,
+            score: 0.0,
+            reasons: Vec::new(),
+        }
+    }
+
+    /// A266 is the nearest, A301 stays free the longest, B101 is the largest and quietest.
+    fn rooms() -> Vec<FreeRoom> {
+        vec![
+            free_room("B101", 1165, 12, 1.0),
+            free_room("A301", 100, 18, 0.5),
+            free_room("A266", 0, 10, 0.0),
+        ]
+    }
+
+    fn catalogue() -> Catalogue {
+        let info = |capacity| RoomInfo {
+            capacity: Some(capacity),
+            ..RoomInfo::default()
+        };
+        Catalogue {
+            rooms: [
+                ("A301".to_owned(), info(30)),
+                ("B101".to_owned(), info(120)),
+            ]
+            .into(),
+        }
+    }
+
+    /// Ranks the rooms and returns their names in order.
+    fn order(weights: &[(&str, f64)]) -> Vec<String> {
+        let catalogue = catalogue();
+        let context = RankingContext {
+            origins: &["A266".parse().unwrap()],
+            start: at(9),
+            catalogue: &catalogue,
+        };
+        let mut rooms = rooms();
+        Ranking::from_weights(weights.iter().copied())
+            .unwrap()
+            .rank(&mut rooms, &context);
+        rooms.iter().map(|room| room.room.to_string()).collect()
+    }
+
+    #[test]
+    fn orders_by_each_strategy() {
+        assert_eq!(order(&[]), ["A266", "A301", "B101"]);
+        assert_eq!(order(&[("nearest", 1.0)]), ["A266", "A301", "B101"]);
+        assert_eq!(order(&[("longest_free", 1.0)]), ["A301", "B101", "A266"]);
+        // A301 has the fewest known seats and scores like A266 without capacity
+        assert_eq!(
+            order(&[("largest_capacity", 1.0)]),
+            ["B101", "A266", "A301"]
+        );
+        assert_eq!(order(&[("same_floor", 1.0)]), ["A266", "A301", "B101"]);
+        assert_eq!(order(&[("quietest", 1.0)]), ["B101", "A301", "A266"]);
+    }
+
+    #[test]
+    fn combines_weighted_strategies() {
+        assert_eq!(
+            order(&[("nearest", 1.0), ("quietest", 1.0)]),
+            ["A301", "A266", "B101"]
+        );
+        assert_eq!(
+            order(&[("nearest", 1.0), ("quietest", 3.0)]),
+            ["B101", "A301", "A266"]
+        );
+    }
+
+    #[test]
+    fn scales_values_between_the_worst_and_the_best_room() {
+        let catalogue = Catalogue::default();
+        let context = RankingContext {
+            origins: &["A266".parse().unwrap()],
+            start: at(9),
+            catalogue: &catalogue,
+        };
+        let mut rooms = rooms();
+        rooms.push(free_room("Audimax", u32::MAX, 11, 0.0));
+        Ranking::default().rank(&mut rooms, &context);
+        let scores: Vec<(String, f64)> = rooms
+            .iter()
+            .map(|room| (room.room.to_string(), room.score))
+            .collect();
+        assert_eq!(scores[0], ("A266".to_owned(), 1.0));
+        assert_eq!(scores[1].0, "A301");
+        assert!((scores[1].1 - (1.0 - 100.0 / 1165.0)).abs() < 1e-9);
+        assert_eq!(scores[2], ("B101".to_owned(), 0.0));
+        // Unreachable rooms cannot be rated and come last
+        assert_eq!(scores[3], ("Audimax".to_owned(), 0.0));
+    }
+
+    #[test]
+    fn equal_values_score_the_same_and_fall_back_to_distance() {
+        // Without catalogue entries no room can be rated by capacity
+        let catalogue = Catalogue::default();
+        let context = RankingContext {
+            origins: &["A266".parse().unwrap()],
+            start: at(9),
+            catalogue: &catalogue,
+        };
+        let mut rooms = rooms();
+        Ranking::from_weights([("largest_capacity", 1.0)])
+            .unwrap()
+            .rank(&mut rooms, &context);
+        assert!(rooms.iter().all(|room| room.score == 0.0));
+        let names: Vec<String> = rooms.iter().map(|room| room.room.to_string()).collect();
+        assert_eq!(names, ["A266", "A301", "B101"]);
+
+        let mut rooms = vec![
+            free_room("A301", 100, 12, 0.5),
+            free_room("A266", 0, 12, 0.5),
+        ];
+        Ranking::from_weights([("quietest", 1.0)])
+            .unwrap()
+            .rank(&mut rooms, &context);
+        assert!(rooms.iter().all(|room| room.score == 1.0));
+        assert_eq!(rooms[0].room.to_string(), "A266");
+    }
+
+    #[test]
+    fn parses_weights() {
+        assert_eq!(parse_weight("nearest=2"), Ok(("nearest".to_owned(), 2.0)));
+        assert_eq!(parse_weight("quietest"), Ok(("quietest".to_owned(), 1.0)));
+        assert!(parse_weight("nearest=-1").is_err());
+        assert!(parse_weight("loudest").is_err());
+    }
+}
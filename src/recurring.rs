@@ -0,0 +1,169 @@
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, Weekday};
+
+use crate::holidays::Closures;
+use crate::locale::Msg;
+use crate::room::Location;
+
+/// A weekly recurring time window, e.g. every Tuesday 16:00-18:00 for eight weeks.
+#[derive(Debug, Clone)]
+pub struct Recurrence {
+    pub weekday: Weekday,
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+    pub first_date: NaiveDate,
+    pub last_date: NaiveDate,
+}
+
+impl Recurrence {
+    /// Creates a recurrence starting on the first `weekday` on or after `from`.
+    /// The series ends after `weeks` occurrences or on `until`, whichever is given.
+    /// Returns None if neither an end date nor a number of weeks is given.
+    pub fn new(
+        weekday: Weekday,
+        start: NaiveTime,
+        end: NaiveTime,
+        from: NaiveDate,
+        weeks: Option<u32>,
+        until: Option<NaiveDate>,
+    ) -> Option<Self> {
+        let offset = (7 + weekday.num_days_from_monday() as i64
+            - from.weekday().num_days_from_monday() as i64)
+            % 7;
+        let first_date = from + Duration::days(offset);
+        let last_date = match (weeks, until) {
+            (Some(weeks), _) => first_date + Duration::weeks(i64::from(weeks) - 1),
+            (None, Some(until)) => until,
+            (None, None) => return None,
+        };
+        Some(Recurrence {
+            weekday,
+            start,
+            end,
+            first_date,
+            last_date,
+        })
+    }
+
+    /// Returns the (start, end) window of every occurrence in the series.
+    pub fn occurrences(&self) -> Vec<(NaiveDateTime, NaiveDateTime)> {
+        let mut occurrences = Vec::new();
+        let mut date = self.first_date;
+        while date <= self.last_date {
+            occurrences.push((date.and_time(self.start), date.and_time(self.end)));
+            date += Duration::weeks(1);
+        }
+        occurrences
+    }
+
+    /// Returns the occurrences on which the campus is open,
+    /// skipping public holidays and campus closure days.
+    pub fn open_occurrences(&self, closures: &Closures) -> Vec<(NaiveDateTime, NaiveDateTime)> {
+        self.occurrences()
+            .into_iter()
+            .filter(|(start, _)| closures.closed_reason(start.date()).is_none())
+            .collect()
+    }
+}
+
+/// Result of a recurring query for a single room.
+/// `taken` holds the dates of all occurrences on which the room is booked.
+#[derive(Debug, Clone)]
+pub struct RecurringRoom {
+    pub room: Location,
+    pub distance: u32,
+    pub free: usize,
+    pub taken: Vec<NaiveDate>,
+}
+
+/// Parses a weekday from German or English names and abbreviations (e.g. "di", "Dienstag", "tue").
+pub fn parse_weekday(src: &str) -> Result<Weekday, String> {
+    let weekday = match src.to_lowercase().as_str() {
+        "mo" | "mon" | "montag" | "monday" => Weekday::Mon,
+        "di" | "tue" | "dienstag" | "tuesday" => Weekday::Tue,
+        "mi" | "wed" | "mittwoch" | "wednesday" => Weekday::Wed,
+        "do" | "thu" | "donnerstag" | "thursday" => Weekday::Thu,
+        "fr" | "fri" | "freitag" | "friday" => Weekday::Fri,
+        "sa" | "sat" | "samstag" | "saturday" => Weekday::Sat,
+        "so" | "sun" | "sonntag" | "sunday" => Weekday::Sun,
+        _ => return Err(Msg::InvalidWeekday(&src).to_string()),
+    };
+    Ok(weekday)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::holidays::Closure;
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, month, day).unwrap()
+    }
+
+    fn tuesdays(weeks: Option<u32>, until: Option<NaiveDate>) -> Option<Recurrence> {
+        let time = |hour| NaiveTime::from_hms_opt(hour, 0, 0).unwrap();
+        // Monday
+        Recurrence::new(Weekday::Tue, time(16), time(18), date(10, 19), weeks, until)
+    }
+
+    fn dates(occurrences: &[(NaiveDateTime, NaiveDateTime)]) -> Vec<NaiveDate> {
+        occurrences.iter().map(|(start, _)| start.date()).collect()
+    }
+
+    #[test]
+    fn runs_for_the_given_number_of_weeks() {
+        let recurrence = tuesdays(Some(3), None).unwrap();
+        assert_eq!(recurrence.first_date, date(10, 20));
+        assert_eq!(recurrence.last_date, date(11, 3));
+        let occurrences = recurrence.occurrences();
+        assert_eq!(
+            dates(&occurrences),
+            [date(10, 20), date(10, 27), date(11, 3)]
+        );
+        assert_eq!(
+            occurrences[0],
+            (
+                date(10, 20).and_hms_opt(16, 0, 0).unwrap(),
+                date(10, 20).and_hms_opt(18, 0, 0).unwrap()
+            )
+        );
+    }
+
+    #[test]
+    fn runs_until_the_given_date() {
+        let until = |day| tuesdays(None, Some(date(11, day))).unwrap().occurrences();
+        assert_eq!(until(10).len(), 4);
+        assert_eq!(until(9).len(), 3);
+        // The number of weeks wins over the end date
+        assert_eq!(
+            tuesdays(Some(1), Some(date(11, 10)))
+                .unwrap()
+                .occurrences()
+                .len(),
+            1
+        );
+        assert!(tuesdays(None, None).is_none());
+        assert!(tuesdays(None, Some(date(10, 19)))
+            .unwrap()
+            .occurrences()
+            .is_empty());
+    }
+
+    #[test]
+    fn skips_holidays_and_closure_days() {
+        let time = |hour| NaiveTime::from_hms_opt(hour, 0, 0).unwrap();
+        // Thursdays around Ascension Day (May 14th)
+        let recurrence =
+            Recurrence::new(Weekday::Thu, time(10), time(12), date(5, 7), Some(3), None).unwrap();
+        let mut closures = Closures::default();
+        assert_eq!(
+            dates(&recurrence.open_occurrences(&closures)),
+            [date(5, 7), date(5, 21)]
+        );
+        closures.closures.push(Closure {
+            from: date(5, 20),
+            to: Some(date(5, 22)),
+            reason: "Betriebsausflug".to_string(),
+        });
+        assert_eq!(dates(&recurrence.open_occurrences(&closures)), [date(5, 7)]);
+    }
+}
@@ -1,45 +1,179 @@
 use serde::{Deserialize, Serialize};
-/// Represents a room identifier consisting of block (char), floor (u8), and room number (u16).
-#[derive(Serialize, Deserialize, Debug)]
+use std::{error::Error, fmt, str::FromStr};
+
+use crate::locale::Msg;
+
+/// Represents a room identifier consisting of block (char), floor (i8, negative for basements),
+/// room number (u16) and an optional letter suffix (e.g. "A266a").
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct RoomId {
     pub block: char,
-    pub floor: u8,
+    pub floor: i8,
     pub number: u16,
+    #[serde(default)]
+    pub suffix: Option<char>,
 }
 
-impl RoomId {
-    /// Parses a string like "B3025" into a RoomId.
-    /// Returns None on invalid format.
-    pub fn from_str(s: &str) -> Option<Self> {
-        let chars: Vec<char> = s.chars().collect();
-        if chars.len() < 3 {
-            return None;
+/// Error returned when a string cannot be parsed into a RoomId.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RoomIdError {
+    /// The string is empty or only whitespace.
+    Empty,
+    /// The first character is not an ASCII letter.
+    InvalidBlock(char),
+    /// There is no floor after the block.
+    MissingFloor(String),
+    /// The floor is not a number in the range of -128 to 127.
+    InvalidFloor(String),
+    /// There is no room number after the floor.
+    MissingNumber(String),
+    /// The room number is not a number, optionally followed by a single letter.
+    InvalidNumber(String),
+}
+
+impl fmt::Display for RoomIdError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RoomIdError::Empty => write!(f, "{}", Msg::RoomNameEmpty),
+            RoomIdError::InvalidBlock(c) => write!(f, "{}", Msg::InvalidBlock(c)),
+            RoomIdError::MissingFloor(s) => write!(f, "{}", Msg::MissingFloor(s)),
+            RoomIdError::InvalidFloor(s) => write!(f, "{}", Msg::InvalidFloor(s)),
+            RoomIdError::MissingNumber(s) => write!(f, "{}", Msg::MissingNumber(s)),
+            RoomIdError::InvalidNumber(s) => write!(f, "{}", Msg::InvalidNumber(s)),
         }
-        let block = chars[0].to_ascii_uppercase();
-        let floor = chars[1].to_digit(10)? as u8;
-        let number = s[2..].parse().ok()?;
-        Some(RoomId {
-            block,
+    }
+}
+
+impl Error for RoomIdError {}
+
+impl FromStr for RoomId {
+    type Err = RoomIdError;
+
+    /// Parses a string like "A266", "a266", "A 266", "A266a", "A2.66" or "A-1.05" into a RoomId.
+    /// Without a dot, the first digit after the block (and an optional minus sign) is the floor
+    /// and the remaining digits are the room number.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let compact: String = s.chars().filter(|c| !c.is_whitespace()).collect();
+        let mut chars = compact.chars();
+        let block = chars.next().ok_or(RoomIdError::Empty)?;
+        if !block.is_ascii_alphabetic() {
+            return Err(RoomIdError::InvalidBlock(block));
+        }
+        let rest = chars.as_str();
+        if rest.is_empty() {
+            return Err(RoomIdError::MissingFloor(s.to_owned()));
+        }
+
+        let (floor, number) = match rest.split_once('.') {
+            Some((floor, number)) => (floor, number),
+            None => {
+                let sign = if rest.starts_with('-') { 1 } else { 0 };
+                let floor_end = rest[sign..]
+                    .char_indices()
+                    .nth(1)
+                    .map_or(rest.len(), |(i, _)| sign + i);
+                rest.split_at(floor_end)
+            }
+        };
+        let floor: i8 = floor
+            .parse()
+            .map_err(|_| RoomIdError::InvalidFloor(s.to_owned()))?;
+
+        let (digits, suffix) = match number.chars().last() {
+            None => return Err(RoomIdError::MissingNumber(s.to_owned())),
+            Some(c) if c.is_ascii_alphabetic() => {
+                (&number[..number.len() - 1], Some(c.to_ascii_lowercase()))
+            }
+            Some(_) => (number, None),
+        };
+        if digits.is_empty() {
+            return Err(RoomIdError::MissingNumber(s.to_owned()));
+        }
+        if !digits.chars().all(|c| c.is_ascii_digit()) {
+            return Err(RoomIdError::InvalidNumber(s.to_owned()));
+        }
+        let number = digits
+            .parse()
+            .map_err(|_| RoomIdError::InvalidNumber(s.to_owned()))?;
+
+        Ok(RoomId {
+            block: block.to_ascii_uppercase(),
             floor,
             number,
+            suffix,
         })
     }
+}
 
-    /// Serializes the RoomId back into a string (e.g. "B3025").
-    #[allow(dead_code)]
-    pub fn to_string(&self) -> String {
-        format!("{}{}{}", self.block, self.floor, self.number)
+impl fmt::Display for RoomId {
+    /// Formats the RoomId back into a string that parses to the same RoomId.
+    /// Floors 0 to 9 with two-digit room numbers are written compactly ("A266", "A206b"),
+    /// all others with a dot between floor and number ("A2.166", "A-1.05", "A12.05").
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if (0..=9).contains(&self.floor) && self.number < 100 {
+            write!(f, "{}{}{:02}", self.block, self.floor, self.number)?;
+        } else {
+            write!(f, "{}{}.{:02}", self.block, self.floor, self.number)?;
+        }
+        if let Some(suffix) = self.suffix {
+            write!(f, "{}", suffix)?;
+        }
+        Ok(())
+    }
+}
+
+/// A place events can take place in: either a numbered room or a named location
+/// that does not follow the room numbering (e.g. "Audimax" or "Online").
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Location {
+    Room(RoomId),
+    Named(String),
+}
+
+impl Location {
+    /// Parses a location name, falling back to a named location if it is not a valid RoomId.
+    /// Returns None for empty names.
+    pub fn parse(name: &str) -> Option<Self> {
+        let name = name.trim();
+        if name.is_empty() {
+            return None;
+        }
+        Some(match name.parse() {
+            Ok(room) => Location::Room(room),
+            Err(_) => Location::Named(name.to_owned()),
+        })
+    }
+
+    /// Returns the RoomId if this is a numbered room.
+    pub fn room_id(&self) -> Option<&RoomId> {
+        match self {
+            Location::Room(room) => Some(room),
+            Location::Named(_) => None,
+        }
+    }
+
+    /// Returns the block of a numbered room.
+    pub fn block(&self) -> Option<char> {
+        self.room_id().map(|room| room.block)
+    }
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Location::Room(room) => write!(f, "{}", room),
+            Location::Named(name) => write!(f, "{}", name),
+        }
     }
 }
 
 /// Calculates a simple distance score between two RoomIds.
-/// Returns u32::MAX if room string cannot be parsed.
+/// Returns u32::MAX for named locations without a room number.
 /// - block difference weighted by 1000
 /// - floor difference weighted by 100
 /// - room number absolute difference
-#[allow(dead_code)]
-pub fn calc_distance(destination: &RoomId, room: &str) -> u32 {
-    if let Some(room_id) = RoomId::from_str(&room) {
+pub fn calc_distance(destination: &RoomId, room: &Location) -> u32 {
+    if let Location::Room(room_id) = room {
         let distance = ((room_id.block as i32 - destination.block as i32).abs() * 1000
             + (room_id.floor as i32 - destination.floor as i32).abs() * 100
             + (room_id.number as i32 - destination.number as i32).abs())
@@ -49,3 +183,72 @@ pub fn calc_distance(destination: &RoomId, room: &str) -> u32 {
     }
     u32::MAX
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn room(block: char, floor: i8, number: u16, suffix: Option<char>) -> RoomId {
+        RoomId {
+            block,
+            floor,
+            number,
+            suffix,
+        }
+    }
+
+    #[test]
+    fn parses_room_names() {
+        assert_eq!("A266".parse(), Ok(room('A', 2, 66, None)));
+        assert_eq!("a 266".parse(), Ok(room('A', 2, 66, None)));
+        assert_eq!("A2.66".parse(), Ok(room('A', 2, 66, None)));
+        assert_eq!("A266A".parse(), Ok(room('A', 2, 66, Some('a'))));
+        assert_eq!("A2166".parse(), Ok(room('A', 2, 166, None)));
+        assert_eq!("A-105".parse(), Ok(room('A', -1, 5, None)));
+        assert_eq!("A-1.05".parse(), Ok(room('A', -1, 5, None)));
+        assert_eq!("A12.05".parse(), Ok(room('A', 12, 5, None)));
+    }
+
+    #[test]
+    fn rejects_invalid_room_names() {
+        assert_eq!(" ".parse::<RoomId>(), Err(RoomIdError::Empty));
+        assert_eq!("1A".parse::<RoomId>(), Err(RoomIdError::InvalidBlock('1')));
+        assert!(matches!(
+            "A".parse::<RoomId>(),
+            Err(RoomIdError::MissingFloor(_))
+        ));
+        assert!(matches!(
+            "A2".parse::<RoomId>(),
+            Err(RoomIdError::MissingNumber(_))
+        ));
+        assert!(matches!(
+            "Ax.66".parse::<RoomId>(),
+            Err(RoomIdError::InvalidFloor(_))
+        ));
+        assert!(matches!(
+            "A2.6x6".parse::<RoomId>(),
+            Err(RoomIdError::InvalidNumber(_))
+        ));
+    }
+
+    #[test]
+    fn displays_unambiguous_names() {
+        assert_eq!(room('A', 2, 66, None).to_string(), "A266");
+        assert_eq!(room('A', 2, 6, Some('a')).to_string(), "A206a");
+        assert_eq!(room('A', 2, 166, None).to_string(), "A2.166");
+        assert_eq!(room('A', -1, 5, None).to_string(), "A-1.05");
+        assert_eq!(room('A', 12, 5, None).to_string(), "A12.05");
+    }
+
+    #[test]
+    fn display_round_trips() {
+        for floor in [-12, -1, 0, 2, 9, 12] {
+            for number in [0, 5, 66, 99, 100, 166, 1234] {
+                for suffix in [None, Some('b')] {
+                    let id = room('B', floor, number, suffix);
+                    assert_eq!(id.to_string().parse(), Ok(id), "{}", id);
+                }
+            }
+        }
+    }
+}
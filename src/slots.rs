@@ -0,0 +1,33 @@
+use chrono::{Duration, NaiveDateTime};
+
+use crate::room::Location;
+
+/// Free time slots of a single room on one day.
+#[derive(Debug, Clone)]
+pub struct RoomSlots {
+    pub room: Location,
+    pub distance: u32,
+    pub slots: Vec<(NaiveDateTime, NaiveDateTime)>,
+}
+
+/// Returns the gaps between the taken intervals of a window that last at least `min_length`.
+/// - `taken`: Sorted, merged intervals inside the window
+pub fn free_slots(
+    taken: &[(NaiveDateTime, NaiveDateTime)],
+    start_time: NaiveDateTime,
+    enddatetime: NaiveDateTime,
+    min_length: Duration,
+) -> Vec<(NaiveDateTime, NaiveDateTime)> {
+    let mut slots = Vec::new();
+    let mut free_from = start_time;
+    for (start, end) in taken {
+        if *start - free_from >= min_length {
+            slots.push((free_from, *start));
+        }
+        free_from = free_from.max(*end);
+    }
+    if enddatetime - free_from >= min_length {
+        slots.push((free_from, enddatetime));
+    }
+    slots
+}
@@ -0,0 +1,271 @@
+use chrono::{Datelike, Duration, NaiveDate, NaiveTime, Weekday};
+use std::collections::BTreeMap;
+
+use crate::free::{self, Booking};
+use crate::locale;
+use crate::room::Location;
+
+/// Booked and total time (in seconds) of a room or group of rooms.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Occupancy {
+    pub busy: i64,
+    pub total: i64,
+}
+
+impl Occupancy {
+    /// Returns the booked share of the total time in percent.
+    pub fn percent(&self) -> f64 {
+        if self.total == 0 {
+            return 0.0;
+        }
+        self.busy as f64 * 100.0 / self.total as f64
+    }
+
+    fn add(&mut self, other: Occupancy) {
+        self.busy += other.busy;
+        self.total += other.total;
+    }
+}
+
+/// Occupancy statistics over a date range, grouped by room, block, floor and weekday/hour.
+/// Floors are keyed by block and floor (e.g. "A2"), weekday/hour cells by the day of the week
+/// (counted from Monday = 0) and the hour of day.
+/// Named locations without a room number only appear in `rooms`.
+#[derive(Debug, Default)]
+pub struct Stats {
+    pub rooms: BTreeMap<Location, Occupancy>,
+    pub blocks: BTreeMap<char, Occupancy>,
+    pub floors: BTreeMap<String, Occupancy>,
+    pub weekday_hours: BTreeMap<(u32, u32), Occupancy>,
+}
+
+/// Sums up the booked time of a room for every hour slot between `first_hour` and `last_hour`
+/// on every day from `from` to `to` (inclusive).
+/// Returns the occupancy per (date, hour) slot.
+fn hourly_occupancy(
+    bookings: &[Booking],
+    from: NaiveDate,
+    to: NaiveDate,
+    first_hour: u32,
+    last_hour: u32,
+) -> Vec<(NaiveDate, u32, Occupancy)> {
+    let mut slots = Vec::new();
+    let mut date = from;
+    while date <= to {
+        for hour in first_hour..last_hour {
+            let start = date.and_time(NaiveTime::from_hms_opt(hour, 0, 0).unwrap());
+            let end = start + Duration::hours(1);
+            let busy = free::taken_intervals(bookings, start, end)
+                .iter()
+                .map(|(start, end)| (*end - *start).num_seconds())
+                .sum();
+            slots.push((date, hour, Occupancy { busy, total: 3600 }));
+        }
+        date += Duration::days(1);
+    }
+    slots
+}
+
+/// Computes occupancy statistics for the given rooms.
+/// - `rooms`: Rooms with their bookings
+/// - `from`, `to`: The date range (inclusive)
+/// - `first_hour`, `last_hour`: The hours of each day that are taken into account
+pub fn compute(
+    rooms: &[(Location, Vec<Booking>)],
+    from: NaiveDate,
+    to: NaiveDate,
+    first_hour: u32,
+    last_hour: u32,
+) -> Stats {
+    let mut stats = Stats::default();
+    for (location, bookings) in rooms {
+        let room_id = location.room_id();
+        for (date, hour, occupancy) in hourly_occupancy(bookings, from, to, first_hour, last_hour) {
+            stats
+                .rooms
+                .entry(location.clone())
+                .or_default()
+                .add(occupancy);
+            stats
+                .weekday_hours
+                .entry((date.weekday().num_days_from_monday(), hour))
+                .or_default()
+                .add(occupancy);
+            if let Some(room_id) = room_id {
+                stats
+                    .blocks
+                    .entry(room_id.block)
+                    .or_default()
+                    .add(occupancy);
+                stats
+                    .floors
+                    .entry(format!("{}{}", room_id.block, room_id.floor))
+                    .or_default()
+                    .add(occupancy);
+            }
+        }
+    }
+    stats
+}
+
+/// Returns the weekday counted from Monday = 0.
+fn weekday_from_index(day: u32) -> Weekday {
+    Weekday::try_from(day as u8).unwrap_or(Weekday::Mon)
+}
+
+/// Escapes a CSV field if it contains separators, quotes or line breaks.
+/// Also used for the `--format csv` output.
+pub fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+impl Stats {
+    /// Renders the weekday/hour occupancy as a terminal heatmap,
+    /// one row per weekday and one cell per hour, shaded by occupancy.
+    pub fn heatmap(&self) -> String {
+        const SHADES: [char; 5] = [' ', '░', '▒', '▓', '█'];
+        let hours: Vec<u32> = {
+            let mut hours: Vec<u32> = self.weekday_hours.keys().map(|(_, hour)| *hour).collect();
+            hours.sort();
+            hours.dedup();
+            hours
+        };
+        let mut weekdays: Vec<u32> = self.weekday_hours.keys().map(|(day, _)| *day).collect();
+        weekdays.dedup();
+
+        let mut out = String::from("    ");
+        for hour in &hours {
+            out.push_str(&format!("{:<3}", hour));
+        }
+        out.push('\n');
+        for weekday in weekdays {
+            out.push_str(&format!(
+                "{:<3} ",
+                locale::weekday(weekday_from_index(weekday))
+            ));
+            for hour in &hours {
+                let percent = self
+                    .weekday_hours
+                    .get(&(weekday, *hour))
+                    .map(Occupancy::percent)
+                    .unwrap_or(0.0);
+                let shade = SHADES[((percent / 25.0).ceil() as usize).min(4)];
+                out.push_str(&shade.to_string().repeat(2));
+                out.push(' ');
+            }
+            out.push('\n');
+        }
+        out.push_str("    ' ' 0%  '░' <=25%  '▒' <=50%  '▓' <=75%  '█' <=100%\n");
+        out
+    }
+
+    /// Exports all statistics as CSV with the columns category, name and occupancy in percent.
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from("category,name,occupancy_percent\n");
+        for (room, occupancy) in &self.rooms {
+            out.push_str(&format!(
+                "room,{},{:.1}\n",
+                csv_field(&room.to_string()),
+                occupancy.percent()
+            ));
+        }
+        for (block, occupancy) in &self.blocks {
+            out.push_str(&format!("block,{},{:.1}\n", block, occupancy.percent()));
+        }
+        for (floor, occupancy) in &self.floors {
+            out.push_str(&format!("floor,{},{:.1}\n", floor, occupancy.percent()));
+        }
+        for ((weekday, hour), occupancy) in &self.weekday_hours {
+            out.push_str(&format!(
+                "weekday_hour,{} {:02},{:.1}\n",
+                weekday_from_index(*weekday),
+                hour,
+                occupancy.percent()
+            ));
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDateTime;
+
+    fn at(day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 10, day)
+            .unwrap()
+            .and_hms_opt(hour, minute, 0)
+            .unwrap()
+    }
+
+    fn room(name: &str) -> Location {
+        Location::parse(name).unwrap()
+    }
+
+    /// Two Mondays (19th and 26th) and the Tuesday after, 08:00 to 12:00.
+    fn stats() -> Stats {
+        let rooms = vec![
+            (
+                room("A266"),
+                vec![
+                    Booking {
+                        start: at(19, 10, 0),
+                        end: at(19, 11, 30),
+                    },
+                    // Only the part before 12:00 is counted
+                    Booking {
+                        start: at(26, 11, 30),
+                        end: at(26, 13, 0),
+                    },
+                ],
+            ),
+            (room("A267"), vec![]),
+            (
+                room("Aula"),
+                vec![Booking {
+                    start: at(27, 8, 0),
+                    end: at(27, 12, 0),
+                }],
+            ),
+        ];
+        let day = |day| NaiveDate::from_ymd_opt(2026, 10, day).unwrap();
+        compute(&rooms, day(19), day(27), 8, 12)
+    }
+
+    #[test]
+    fn sums_up_each_weekday_and_hour() {
+        let stats = stats();
+        let cell = |weekday, hour| stats.weekday_hours[&(weekday, hour)];
+        // Three rooms on two Mondays
+        assert_eq!(cell(0, 10).total, 3 * 2 * 3600);
+        assert_eq!(cell(0, 10).busy, 3600);
+        assert_eq!(cell(0, 11).busy, 3600);
+        assert_eq!(cell(0, 8).busy, 0);
+        // Only one Wednesday, the Aula is booked on the second Tuesday
+        assert_eq!(cell(2, 9).total, 3 * 3600);
+        assert_eq!(cell(1, 9).busy, 3600);
+        assert_eq!(stats.weekday_hours.len(), 7 * 4);
+        assert!(!stats.weekday_hours.contains_key(&(0, 12)));
+    }
+
+    #[test]
+    fn groups_rooms_by_block_and_floor() {
+        let stats = stats();
+        let a266 = stats.rooms[&room("A266")];
+        assert_eq!(a266.busy, 3600 + 1800 + 1800);
+        assert_eq!(a266.total, 9 * 4 * 3600);
+        assert_eq!(stats.rooms[&room("Aula")].busy, 4 * 3600);
+        // Named locations only appear per room
+        assert_eq!(stats.blocks[&'A'].busy, a266.busy);
+        assert_eq!(stats.blocks[&'A'].total, 2 * a266.total);
+        assert_eq!(stats.floors.keys().collect::<Vec<_>>(), ["A2"]);
+        assert!(stats
+            .to_csv()
+            .contains("weekday_hour,Mon 10,16.7\nweekday_hour,Mon 11,16.7\n"));
+    }
+}
@@ -0,0 +1,336 @@
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, Weekday};
+
+use crate::locale::Msg;
+use crate::recurring::parse_weekday;
+
+/// Parses a date given as "YYYY-MM-DD", "DD.MM.YYYY", ISO week date ("2026-W43-2", "W43-2"),
+/// keyword ("heute", "morgen", "übermorgen", "gestern" or "today", "tomorrow", "yesterday"),
+/// weekday ("mo", "montag", "fri", ...) or relative to today ("in 3d", "in 2 Tagen").
+/// Weekdays mean the next such day, today included.
+pub fn parse_date(src: &str, today: NaiveDate) -> Result<NaiveDate, String> {
+    let text = src.trim().to_lowercase();
+    let offset = match text.as_str() {
+        "heute" | "today" => Some(0),
+        "morgen" | "tomorrow" => Some(1),
+        "übermorgen" | "uebermorgen" => Some(2),
+        "gestern" | "yesterday" => Some(-1),
+        _ => None,
+    };
+    if let Some(days) = offset {
+        return Ok(today + Duration::days(days));
+    }
+    if let Ok(weekday) = parse_weekday(&text) {
+        let ahead =
+            (weekday.num_days_from_monday() + 7 - today.weekday().num_days_from_monday()) % 7;
+        return Ok(today + Duration::days(ahead as i64));
+    }
+    if let Some(length) = text.strip_prefix("in ") {
+        let length = parse_duration(length)?;
+        return today
+            .and_time(NaiveTime::MIN)
+            .checked_add_signed(length)
+            .map(|datetime| datetime.date())
+            .ok_or_else(|| Msg::InvalidDate(&src).to_string());
+    }
+    NaiveDate::parse_from_str(&text, "%Y-%m-%d")
+        .or_else(|_| NaiveDate::parse_from_str(&text, "%d.%m.%Y"))
+        .or_else(|_| parse_week_date(&text, today.year()))
+        .map_err(|_| Msg::InvalidDate(&src).to_string())
+}
+
+/// Parses an ISO week like "2025-W46", "2025W46", "W46" or "46" (year of `today`)
+/// into the Monday of that week.
+pub fn parse_week(src: &str, today: NaiveDate) -> Result<NaiveDate, String> {
+    let text = src.trim();
+    let result = match text.parse::<u32>() {
+        Ok(week) => NaiveDate::from_isoywd_opt(today.year(), week, Weekday::Mon).ok_or(()),
+        Err(_) => parse_week_date(&text.to_lowercase(), today.year()),
+    };
+    result.map_err(|_| Msg::InvalidWeek(&src).to_string())
+}
+
+/// Parses an ISO week date ("2026-W43-2", "2026W432", "W43-2")
+/// or week ("2026-W43", "W43", Monday of that week).
+/// The year defaults to `year`.
+fn parse_week_date(text: &str, year: i32) -> Result<NaiveDate, ()> {
+    let (year_part, rest) = text.split_once('w').ok_or(())?;
+    let year_part = year_part.trim_end_matches('-');
+    let year = if year_part.is_empty() {
+        year
+    } else {
+        year_part.parse().map_err(|_| ())?
+    };
+    // Week and day are either separated by a dash or written as three digits
+    let (week, day) = match rest.split_once('-') {
+        Some((week, day)) => (week, Some(day)),
+        None if rest.len() == 3 && rest.bytes().all(|b| b.is_ascii_digit()) => {
+            (&rest[..2], Some(&rest[2..]))
+        }
+        None => (rest, None),
+    };
+    let week: u32 = week.parse().map_err(|_| ())?;
+    let weekday = match day {
+        Some(day) => {
+            let day: u8 = day.parse().map_err(|_| ())?;
+            Weekday::try_from(day.checked_sub(1).ok_or(())?).map_err(|_| ())?
+        }
+        None => Weekday::Mon,
+    };
+    NaiveDate::from_isoywd_opt(year, week, weekday).ok_or(())
+}
+
+/// Parses a time of day given as "HH:MM", "14 Uhr", "14h" or keyword
+/// ("jetzt", "mittag" or "now", "noon").
+/// Times relative to now ("in 30m") can fall on another day, see `parse_time_input`.
+pub fn parse_time(src: &str, now: NaiveDateTime) -> Result<NaiveTime, String> {
+    let text = src.trim().to_lowercase();
+    match text.as_str() {
+        "jetzt" | "now" => return Ok(now.time()),
+        "mittag" | "noon" => return Ok(NaiveTime::from_hms_opt(12, 0, 0).unwrap()),
+        _ => {}
+    }
+    if let Some(hour) = text
+        .strip_suffix("uhr")
+        .or_else(|| text.strip_suffix('h'))
+        .and_then(|hour| hour.trim().parse::<u32>().ok())
+    {
+        return NaiveTime::from_hms_opt(hour, 0, 0)
+            .ok_or_else(|| Msg::InvalidTime(&src).to_string());
+    }
+    NaiveTime::parse_from_str(&text, "%H:%M").map_err(|_| Msg::InvalidTime(&src).to_string())
+}
+
+/// A start or end time: a time of day, or a point in time relative to now ("in 30m"),
+/// which keeps its own date as it can fall on another day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeInput {
+    OfDay(NaiveTime),
+    Relative(NaiveDateTime),
+}
+
+impl TimeInput {
+    /// Returns the point in time on the given date; relative times keep their own date.
+    pub fn on(self, date: NaiveDate) -> NaiveDateTime {
+        match self {
+            TimeInput::OfDay(time) => date.and_time(time),
+            TimeInput::Relative(datetime) => datetime,
+        }
+    }
+
+    /// Returns the time of day.
+    pub fn time(self) -> NaiveTime {
+        match self {
+            TimeInput::OfDay(time) => time,
+            TimeInput::Relative(datetime) => datetime.time(),
+        }
+    }
+}
+
+/// Parses a time of day (see `parse_time`) or a time relative to now ("in 30m", "in 2 Stunden").
+pub fn parse_time_input(src: &str, now: NaiveDateTime) -> Result<TimeInput, String> {
+    if src.trim().to_lowercase().starts_with("in ") {
+        return parse_datetime(src, now).map(TimeInput::Relative);
+    }
+    parse_time(src, now).map(TimeInput::OfDay)
+}
+
+/// Parses a point in time from a date and a time expression, e.g. "Fr 14:00", "morgen 9 Uhr",
+/// "2026-W43-2 10:00", "2026-10-21T14:00", "in 30m" or "jetzt".
+/// Without a date today is used, without a time the current time (like `-d`).
+pub fn parse_datetime(src: &str, now: NaiveDateTime) -> Result<NaiveDateTime, String> {
+    let text = src.trim().to_lowercase();
+    if matches!(text.as_str(), "jetzt" | "now") {
+        return Ok(now);
+    }
+    if let Some(length) = text.strip_prefix("in ") {
+        if let Ok(length) = parse_duration(length) {
+            return now
+                .checked_add_signed(length)
+                .ok_or_else(|| Msg::InvalidDateTime(&src).to_string());
+        }
+    }
+    if let Ok(datetime) = NaiveDateTime::parse_from_str(&text, "%Y-%m-%dt%H:%M") {
+        return Ok(datetime);
+    }
+    // Try every split into a date part and a time part
+    let words: Vec<&str> = text.split_whitespace().collect();
+    for split in (0..=words.len()).rev() {
+        let (date, time) = (words[..split].join(" "), words[split..].join(" "));
+        let date = if date.is_empty() {
+            Ok(now.date())
+        } else {
+            parse_date(&date, now.date())
+        };
+        let time = if time.is_empty() {
+            Ok(now.time())
+        } else {
+            parse_time(&time, now)
+        };
+        if let (Ok(date), Ok(time)) = (date, time) {
+            return Ok(date.and_time(time));
+        }
+    }
+    Err(Msg::InvalidDateTime(&src).to_string())
+}
+
+/// Parses a duration like "1h30m", "90m", "2h", "1,5h", "1 Stunde 30 Minuten" or "2 days".
+/// A plain number counts as minutes. Durations shorter than a minute are rejected.
+pub fn parse_duration(src: &str) -> Result<Duration, String> {
+    let invalid = || Msg::InvalidDuration(&src).to_string();
+    let text = src.trim().to_lowercase().replace(',', ".");
+    let mut total = 0.0;
+    let mut rest = text.as_str();
+    if let Ok(minutes) = text.parse::<u32>() {
+        total = minutes as f64;
+        rest = "";
+    }
+    while !rest.trim_start().is_empty() {
+        rest = rest.trim_start();
+        let number_end = rest
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .ok_or_else(invalid)?;
+        let number: f64 = rest[..number_end].parse().map_err(|_| invalid())?;
+        rest = rest[number_end..].trim_start();
+        let unit_end = rest
+            .find(|c: char| !c.is_alphabetic())
+            .unwrap_or(rest.len());
+        let minutes = match &rest[..unit_end] {
+            "d" | "t" | "tag" | "tage" | "tagen" | "day" | "days" => 24.0 * 60.0,
+            "h" | "std" | "stunde" | "stunden" | "hour" | "hours" => 60.0,
+            "m" | "min" | "mins" | "minute" | "minuten" | "minutes" => 1.0,
+            _ => return Err(invalid()),
+        };
+        total += number * minutes;
+        rest = &rest[unit_end..];
+    }
+    let minutes = total.round();
+    if minutes < 1.0 {
+        return Err(invalid());
+    }
+    Duration::try_minutes(minutes as i64).ok_or_else(invalid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, month, day).unwrap()
+    }
+
+    fn time(hour: u32, minute: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    /// Monday, 19 October 2026, 23:50.
+    fn now() -> NaiveDateTime {
+        date(10, 19).and_time(time(23, 50))
+    }
+
+    #[test]
+    fn parses_dates() {
+        let today = date(10, 19);
+        assert_eq!(parse_date("2026-10-21", today), Ok(date(10, 21)));
+        assert_eq!(parse_date("21.10.2026", today), Ok(date(10, 21)));
+        assert_eq!(parse_date("2026-W43-3", today), Ok(date(10, 21)));
+        assert_eq!(parse_date("W43-3", today), Ok(date(10, 21)));
+        assert_eq!(parse_date("Morgen", today), Ok(date(10, 20)));
+        assert_eq!(parse_date("gestern", today), Ok(date(10, 18)));
+        assert_eq!(parse_date("mo", today), Ok(today));
+        assert_eq!(parse_date("freitag", today), Ok(date(10, 23)));
+        assert_eq!(parse_date("in 3d", today), Ok(date(10, 22)));
+        assert!(parse_date("32.10.2026", today).is_err());
+        assert!(parse_date("in 0d", today).is_err());
+    }
+
+    #[test]
+    fn parses_weeks() {
+        let today = date(10, 19);
+        assert_eq!(parse_week("2026-W43", today), Ok(date(10, 19)));
+        assert_eq!(parse_week("2026W43", today), Ok(date(10, 19)));
+        assert_eq!(parse_week("W44", today), Ok(date(10, 26)));
+        assert_eq!(parse_week("44", today), Ok(date(10, 26)));
+        assert!(parse_week("54", today).is_err());
+    }
+
+    #[test]
+    fn parses_times() {
+        assert_eq!(parse_time("14:30", now()), Ok(time(14, 30)));
+        assert_eq!(parse_time("14 Uhr", now()), Ok(time(14, 0)));
+        assert_eq!(parse_time("9h", now()), Ok(time(9, 0)));
+        assert_eq!(parse_time("Mittag", now()), Ok(time(12, 0)));
+        assert_eq!(parse_time("jetzt", now()), Ok(time(23, 50)));
+        assert!(parse_time("25:00", now()).is_err());
+        assert!(parse_time("in 30m", now()).is_err());
+    }
+
+    #[test]
+    fn parses_relative_times_with_their_date() {
+        assert_eq!(
+            parse_time_input("14:30", now()),
+            Ok(TimeInput::OfDay(time(14, 30)))
+        );
+        let relative = parse_time_input("in 30m", now()).unwrap();
+        assert_eq!(
+            relative,
+            TimeInput::Relative(date(10, 20).and_time(time(0, 20)))
+        );
+        assert_eq!(
+            relative.on(date(10, 19)),
+            date(10, 20).and_time(time(0, 20))
+        );
+        assert_eq!(relative.time(), time(0, 20));
+        assert_eq!(
+            TimeInput::OfDay(time(9, 0)).on(date(10, 21)),
+            date(10, 21).and_time(time(9, 0))
+        );
+        assert!(parse_time_input("in 0m", now()).is_err());
+    }
+
+    #[test]
+    fn parses_points_in_time() {
+        assert_eq!(
+            parse_datetime("Fr 14:00", now()),
+            Ok(date(10, 23).and_time(time(14, 0)))
+        );
+        assert_eq!(
+            parse_datetime("morgen 9 Uhr", now()),
+            Ok(date(10, 20).and_time(time(9, 0)))
+        );
+        assert_eq!(
+            parse_datetime("2026-10-21T14:00", now()),
+            Ok(date(10, 21).and_time(time(14, 0)))
+        );
+        assert_eq!(
+            parse_datetime("2026-W43-2 10:00", now()),
+            Ok(date(10, 20).and_time(time(10, 0)))
+        );
+        // Relative times continue on the next day
+        assert_eq!(
+            parse_datetime("in 30m", now()),
+            Ok(date(10, 20).and_time(time(0, 20)))
+        );
+        assert_eq!(
+            parse_datetime("16:00", now()),
+            Ok(date(10, 19).and_time(time(16, 0)))
+        );
+        assert!(parse_datetime("irgendwann", now()).is_err());
+    }
+
+    #[test]
+    fn parses_durations() {
+        assert_eq!(parse_duration("90"), Ok(Duration::minutes(90)));
+        assert_eq!(parse_duration("1h30m"), Ok(Duration::minutes(90)));
+        assert_eq!(parse_duration("1,5h"), Ok(Duration::minutes(90)));
+        assert_eq!(
+            parse_duration("1 Stunde 30 Minuten"),
+            Ok(Duration::minutes(90))
+        );
+        assert_eq!(parse_duration("2 days"), Ok(Duration::days(2)));
+        assert!(parse_duration("0").is_err());
+        assert!(parse_duration("0h").is_err());
+        assert!(parse_duration("20s").is_err());
+        assert!(parse_duration("999999999999d").is_err());
+        assert!(parse_duration("1x").is_err());
+    }
+}
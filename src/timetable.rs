@@ -0,0 +1,110 @@
+use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime};
+use std::fmt;
+
+use crate::free::{self, Booking};
+use crate::hours::DayHours;
+use crate::slots;
+
+/// Status of a calendar event as given by the course calendars.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Tentative,
+    Confirmed,
+    Cancelled,
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Status::Tentative => "tentative",
+            Status::Confirmed => "confirmed",
+            Status::Cancelled => "cancelled",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// A single event of a room calendar with its title, the courses attending and its status.
+#[derive(Debug, Clone)]
+pub struct RoomEvent {
+    pub start: NaiveDateTime,
+    pub end: NaiveDateTime,
+    pub title: String,
+    pub courses: Vec<String>,
+    pub status: Option<Status>,
+}
+
+/// A line of a timetable: either an event or a free gap between events.
+#[derive(Debug, Clone)]
+pub enum Entry {
+    Event(RoomEvent),
+    Free {
+        start: NaiveDateTime,
+        end: NaiveDateTime,
+    },
+}
+
+impl Entry {
+    /// Returns the start of the event or gap.
+    pub fn start(&self) -> NaiveDateTime {
+        match self {
+            Entry::Event(event) => event.start,
+            Entry::Free { start, .. } => *start,
+        }
+    }
+}
+
+/// Timetable of a room on one day.
+/// `hours` are the opening hours of the room's block, `closed` the reason if the campus is closed.
+#[derive(Debug, Clone)]
+pub struct TimetableDay {
+    pub date: NaiveDate,
+    pub hours: Option<DayHours>,
+    pub closed: Option<String>,
+    pub entries: Vec<Entry>,
+}
+
+/// Builds the timetable of a room on one day from its events.
+/// Free gaps are only listed within the opening hours and not on closed days,
+/// cancelled events do not take up time.
+pub fn day(
+    events: &[RoomEvent],
+    date: NaiveDate,
+    hours: Option<DayHours>,
+    closed: Option<String>,
+) -> TimetableDay {
+    let day_start = date.and_time(NaiveTime::MIN);
+    let day_end = day_start + Duration::days(1);
+    let mut entries: Vec<Entry> = events
+        .iter()
+        .filter(|event| event.start < day_end && event.end > day_start)
+        .cloned()
+        .map(Entry::Event)
+        .collect();
+
+    if let (Some(hours), None) = (hours, &closed) {
+        let (open, close) = (date.and_time(hours.open), date.and_time(hours.close));
+        let bookings: Vec<Booking> = events
+            .iter()
+            .filter(|event| event.status != Some(Status::Cancelled))
+            .map(|event| Booking {
+                start: event.start,
+                end: event.end,
+            })
+            .collect();
+        let taken = free::taken_intervals(&bookings, open, close);
+        entries.extend(
+            slots::free_slots(&taken, open, close, Duration::minutes(1))
+                .into_iter()
+                .map(|(start, end)| Entry::Free { start, end }),
+        );
+    }
+    entries.sort_by_key(Entry::start);
+
+    TimetableDay {
+        date,
+        hours,
+        closed,
+        entries,
+    }
+}
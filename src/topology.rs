@@ -0,0 +1,429 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, BinaryHeap, HashMap},
+    fmt, fs, io,
+    path::Path,
+    str::FromStr,
+};
+
+use crate::locale::Msg;
+use crate::room::{calc_distance, Location, RoomId};
+
+const TOPOLOGY_TOML: &str = "topology.toml";
+const TOPOLOGY_JSON: &str = "topology.json";
+
+/// Kind of a connection between two points of the campus.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ConnectionKind {
+    Corridor,
+    Bridge,
+    Stairs,
+    Elevator,
+}
+
+/// A walkable connection between two points, e.g. a staircase from "A2.50" to "A3.50"
+/// or a bridge from "A1.10" to "B1.90". Points are given like room ids and describe
+/// where on a floor the connection starts and ends. Connections can be used in both directions.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Connection {
+    pub kind: ConnectionKind,
+    pub from: String,
+    pub to: String,
+    pub seconds: u32,
+}
+
+/// Walking settings of a single block.
+/// `room_step` overrides the walking time per room number along a corridor.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Block {
+    pub room_step: Option<u32>,
+}
+
+/// Campus topology loaded from topology.toml or topology.json.
+/// Rooms on the same floor of a block are connected by a corridor, where walking from one room
+/// to the next takes `room_step` seconds per room number. Everything else (stairs, elevators,
+/// bridges between blocks) has to be listed in `connections` with its walking time in seconds.
+/// `neighbours` lists the rooms next to a room where the room numbers do not tell,
+/// e.g. rooms across the corridor.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Topology {
+    #[serde(default = "default_room_step")]
+    pub room_step: u32,
+    #[serde(default)]
+    pub blocks: BTreeMap<char, Block>,
+    #[serde(default)]
+    pub connections: Vec<Connection>,
+    #[serde(default)]
+    pub neighbours: BTreeMap<String, Vec<String>>,
+}
+
+fn default_room_step() -> u32 {
+    2
+}
+
+impl Topology {
+    /// Loads the campus topology from topology.toml, or from topology.json if there is no TOML file.
+    /// Returns None if neither file exists.
+    pub fn load() -> io::Result<Option<Self>> {
+        if Path::new(TOPOLOGY_TOML).exists() {
+            let text = fs::read_to_string(TOPOLOGY_TOML)?;
+            toml::from_str(&text).map(Some).map_err(io::Error::other)
+        } else if Path::new(TOPOLOGY_JSON).exists() {
+            let text = fs::read_to_string(TOPOLOGY_JSON)?;
+            serde_json::from_str(&text)
+                .map(Some)
+                .map_err(io::Error::other)
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Returns the neighbours listed for a room, in both directions,
+    /// or None if the room is not part of any neighbour list.
+    pub fn listed_neighbours(&self, location: &Location) -> Option<Vec<Location>> {
+        let mut listed = false;
+        let mut found = Vec::new();
+        for (room, neighbours) in &self.neighbours {
+            let room = Location::parse(room);
+            let neighbours: Vec<Location> = neighbours
+                .iter()
+                .filter_map(|name| Location::parse(name))
+                .collect();
+            if room.as_ref() == Some(location) {
+                listed = true;
+                found.extend(neighbours);
+            } else if neighbours.contains(location) {
+                listed = true;
+                found.extend(room);
+            }
+        }
+        listed.then_some(found)
+    }
+
+    /// Returns the walking time per room number in the given block.
+    fn room_step(&self, block: char) -> u32 {
+        self.blocks
+            .get(&block)
+            .and_then(|block| block.room_step)
+            .unwrap_or(self.room_step)
+    }
+
+    /// Returns the walking time along the corridor between two points on the same floor of a block,
+    /// or None if they are on different floors or in different blocks.
+    fn corridor(&self, from: &RoomId, to: &RoomId) -> Option<u32> {
+        if from.block != to.block || from.floor != to.floor {
+            return None;
+        }
+        Some(from.number.abs_diff(to.number) as u32 * self.room_step(from.block))
+    }
+
+    /// Parses the end points of all connections that can be used.
+    /// Connections with invalid end points are ignored.
+    /// - `step_free`: If true, stairs are left out.
+    fn edges(&self, step_free: bool) -> Vec<(RoomId, RoomId, u32)> {
+        self.connections
+            .iter()
+            .filter(|connection| !step_free || connection.kind != ConnectionKind::Stairs)
+            .filter_map(|connection| {
+                Some((
+                    connection.from.parse().ok()?,
+                    connection.to.parse().ok()?,
+                    connection.seconds,
+                ))
+            })
+            .collect()
+    }
+
+    /// Calculates the shortest walking time from the origin to every connection end point.
+    /// - `step_free`: If true, only routes without stairs are used.
+    pub fn walking_times(&self, origin: &RoomId, step_free: bool) -> WalkingTimes<'_> {
+        let edges = self.edges(step_free);
+        let mut nodes: Vec<RoomId> = edges.iter().flat_map(|(a, b, _)| [*a, *b]).collect();
+        nodes.push(*origin);
+        nodes.sort();
+        nodes.dedup();
+
+        let mut times: HashMap<RoomId, u32> = HashMap::new();
+        let mut queue = BinaryHeap::new();
+        queue.push(Reverse((0, *origin)));
+        while let Some(Reverse((time, node))) = queue.pop() {
+            if times.contains_key(&node) {
+                continue;
+            }
+            times.insert(node, time);
+            for next in &nodes {
+                if let Some(step) = self.corridor(&node, next) {
+                    queue.push(Reverse((time + step, *next)));
+                }
+            }
+            for (a, b, seconds) in &edges {
+                if *a == node {
+                    queue.push(Reverse((time + seconds, *b)));
+                } else if *b == node {
+                    queue.push(Reverse((time + seconds, *a)));
+                }
+            }
+        }
+        WalkingTimes {
+            topology: self,
+            times,
+        }
+    }
+}
+
+/// Shortest walking times from an origin room to all connection end points of a topology.
+#[derive(Debug)]
+pub struct WalkingTimes<'a> {
+    topology: &'a Topology,
+    times: HashMap<RoomId, u32>,
+}
+
+impl WalkingTimes<'_> {
+    /// Returns the walking time in seconds to the given room,
+    /// or None if it cannot be reached.
+    pub fn to(&self, room: &RoomId) -> Option<u32> {
+        self.times
+            .iter()
+            .filter_map(|(node, time)| Some(time + self.topology.corridor(node, room)?))
+            .min()
+    }
+}
+
+/// Distances from an origin room, either as walking times over the campus topology
+/// or, without topology, as the simple block/floor/number score of `calc_distance`.
+#[derive(Debug)]
+pub enum Distances<'a> {
+    Formula(RoomId),
+    Walking(WalkingTimes<'a>),
+}
+
+impl<'a> Distances<'a> {
+    /// Prepares the distance calculation from the origin room.
+    /// - `topology`: The campus topology, or None to use the simple formula
+    /// - `step_free`: If true, only routes without stairs are used
+    pub fn new(topology: Option<&'a Topology>, origin: &RoomId, step_free: bool) -> Self {
+        match topology {
+            Some(topology) => Distances::Walking(topology.walking_times(origin, step_free)),
+            None => Distances::Formula(*origin),
+        }
+    }
+
+    /// Returns the distance to the given location.
+    /// Named locations and rooms that cannot be reached return u32::MAX.
+    pub fn to(&self, location: &Location) -> u32 {
+        match self {
+            Distances::Formula(origin) => calc_distance(origin, location),
+            Distances::Walking(times) => location
+                .room_id()
+                .and_then(|room| times.to(room))
+                .unwrap_or(u32::MAX),
+        }
+    }
+}
+
+/// How the distances of several origin rooms are combined into one.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Aggregate {
+    /// Total distance of everyone.
+    #[default]
+    Sum,
+    /// Distance of whoever has the longest way.
+    Max,
+}
+
+impl FromStr for Aggregate {
+    type Err = String;
+
+    /// Parses "sum" (or "total") and "max".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "sum" | "total" | "summe" => Ok(Aggregate::Sum),
+            "max" | "maximum" => Ok(Aggregate::Max),
+            _ => Err(Msg::InvalidAggregate(&s).to_string()),
+        }
+    }
+}
+
+impl fmt::Display for Aggregate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Aggregate::Sum => write!(f, "sum"),
+            Aggregate::Max => write!(f, "max"),
+        }
+    }
+}
+
+impl Aggregate {
+    /// Combines the distances of all origins.
+    /// Returns u32::MAX if the location cannot be reached from one of them.
+    pub fn combine(self, distances: &[u32]) -> u32 {
+        if distances.contains(&u32::MAX) {
+            return u32::MAX;
+        }
+        match self {
+            Aggregate::Sum => distances
+                .iter()
+                .fold(0u32, |total, distance| total.saturating_add(*distance)),
+            Aggregate::Max => distances.iter().copied().max().unwrap_or(0),
+        }
+    }
+}
+
+/// The rooms a search starts from, e.g. where the members of a group sit,
+/// and how their distances are combined.
+#[derive(Debug, Clone)]
+pub struct Origins {
+    pub rooms: Vec<RoomId>,
+    pub aggregate: Aggregate,
+}
+
+impl Origins {
+    /// Creates origins for a single person.
+    pub fn single(room: RoomId) -> Self {
+        Origins {
+            rooms: vec![room],
+            aggregate: Aggregate::Sum,
+        }
+    }
+
+    /// Prepares the distance calculation from all origin rooms.
+    /// - `topology`: The campus topology, or None to use the simple formula
+    /// - `step_free`: If true, only routes without stairs are used
+    pub fn distances<'a>(
+        &self,
+        topology: Option<&'a Topology>,
+        step_free: bool,
+    ) -> GroupDistances<'a> {
+        GroupDistances {
+            distances: self
+                .rooms
+                .iter()
+                .map(|origin| Distances::new(topology, origin, step_free))
+                .collect(),
+            aggregate: self.aggregate,
+        }
+    }
+}
+
+/// Distances from several origin rooms at once.
+#[derive(Debug)]
+pub struct GroupDistances<'a> {
+    distances: Vec<Distances<'a>>,
+    aggregate: Aggregate,
+}
+
+impl GroupDistances<'_> {
+    /// Returns the combined distance to the given location and the distance from every origin.
+    pub fn to(&self, location: &Location) -> (u32, Vec<u32>) {
+        let each: Vec<u32> = self
+            .distances
+            .iter()
+            .map(|distances| distances.to(location))
+            .collect();
+        (self.aggregate.combine(&each), each)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn room(name: &str) -> RoomId {
+        name.parse().unwrap()
+    }
+
+    fn connection(kind: ConnectionKind, from: &str, to: &str, seconds: u32) -> Connection {
+        Connection {
+            kind,
+            from: from.to_owned(),
+            to: to.to_owned(),
+            seconds,
+        }
+    }
+
+    /// Block A with stairs (fast) and an elevator (slow) from floor 1 to 2,
+    /// block B with long corridors and a bridge from A1.10 to B1.90, and block C on its own.
+    fn topology() -> Topology {
+        Topology {
+            room_step: 2,
+            blocks: [('B', Block { room_step: Some(5) })].into(),
+            connections: vec![
+                connection(ConnectionKind::Stairs, "A1.50", "A2.50", 20),
+                connection(ConnectionKind::Elevator, "A1.00", "A2.00", 60),
+                connection(ConnectionKind::Bridge, "A1.10", "B1.90", 30),
+                connection(ConnectionKind::Corridor, "X", "A1.00", 1),
+            ],
+            neighbours: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn walks_along_corridors() {
+        let topology = topology();
+        let times = topology.walking_times(&room("A120"), false);
+        assert_eq!(times.to(&room("A120")), Some(0));
+        assert_eq!(times.to(&room("A130")), Some(20));
+        assert_eq!(times.to(&room("A105")), Some(30));
+    }
+
+    #[test]
+    fn takes_the_shortest_route_between_floors() {
+        let topology = topology();
+        // A1.20 -> A1.50 (60) -> stairs (20) -> A2.50 -> A2.40 (20)
+        let times = topology.walking_times(&room("A120"), false);
+        assert_eq!(times.to(&room("A240")), Some(100));
+        // Without stairs: A1.20 -> A1.00 (40) -> elevator (60) -> A2.00 -> A2.40 (80)
+        let times = topology.walking_times(&room("A120"), true);
+        assert_eq!(times.to(&room("A240")), Some(180));
+    }
+
+    #[test]
+    fn crosses_bridges_with_the_room_step_of_each_block() {
+        let topology = topology();
+        // A1.20 -> A1.10 (20) -> bridge (30) -> B1.90 -> B1.80 (10 numbers at 5 seconds)
+        let times = topology.walking_times(&room("A120"), false);
+        assert_eq!(times.to(&room("B180")), Some(100));
+        assert_eq!(times.to(&room("B280")), None);
+    }
+
+    #[test]
+    fn unreachable_rooms_are_u32_max() {
+        let topology = topology();
+        let distances = Distances::new(Some(&topology), &room("A120"), false);
+        assert_eq!(distances.to(&Location::Room(room("C101"))), u32::MAX);
+        assert_eq!(
+            distances.to(&Location::Named("Audimax".to_owned())),
+            u32::MAX
+        );
+        assert_eq!(distances.to(&Location::Room(room("A130"))), 20);
+        // Without topology the formula is used
+        let distances = Distances::new(None, &room("A120"), false);
+        assert_eq!(distances.to(&Location::Room(room("C101"))), 2019);
+    }
+
+    #[test]
+    fn ignores_connections_with_invalid_end_points() {
+        let edges = topology().edges(false);
+        assert_eq!(edges.len(), 3);
+        assert_eq!(topology().edges(true).len(), 2);
+    }
+
+    #[test]
+    fn combines_the_distances_of_several_origins() {
+        let topology = topology();
+        let origins = Origins {
+            rooms: vec![room("A120"), room("A130")],
+            aggregate: Aggregate::Max,
+        };
+        let distances = origins.distances(Some(&topology), false);
+        assert_eq!(
+            distances.to(&Location::Room(room("A110"))),
+            (40, vec![20, 40])
+        );
+        assert_eq!(distances.to(&Location::Room(room("C101"))).0, u32::MAX);
+        assert_eq!(Aggregate::Sum.combine(&[20, 40]), 60);
+    }
+}
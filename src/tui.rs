@@ -0,0 +1,380 @@
+use chrono::{Datelike, Duration, Local, NaiveDateTime, NaiveTime, Timelike};
+use dhbw_roomfinder::campus::{Availability, Campus};
+use dhbw_roomfinder::filter::FilterSet;
+use dhbw_roomfinder::locale::{self, Msg};
+use dhbw_roomfinder::ranking::Ranking;
+use dhbw_roomfinder::room::Location;
+use dhbw_roomfinder::timetable::{Entry, Status, TimetableDay};
+use dhbw_roomfinder::topology::Origins;
+use dhbw_roomfinder::FreeRoom;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, LineGauge, Paragraph, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+use std::collections::BTreeMap;
+use std::io;
+
+use crate::output::known_distance;
+
+/// Minutes the time slider moves per key press, also the length of the window rooms have to be free.
+const STEP_MINUTES: i64 = 15;
+
+/// State of the terminal UI: the loaded campus, the time on the slider,
+/// the room grid with the selected room and the ranked free rooms for the current origin.
+struct App {
+    campus: Campus,
+    filter: FilterSet,
+    ranking: Ranking,
+    origins: Origins,
+    at: NaiveDateTime,
+    rows: Vec<(String, Vec<Location>)>,
+    selected: (usize, usize),
+    free: Vec<FreeRoom>,
+    timetable: Option<TimetableDay>,
+}
+
+impl App {
+    /// Groups the rooms of the index by block and floor, named locations go into a last row.
+    fn new(campus: Campus, filter: FilterSet, ranking: Ranking, origins: Origins) -> Self {
+        let mut floors: BTreeMap<(char, i8), Vec<Location>> = BTreeMap::new();
+        let mut named = Vec::new();
+        for location in campus.index.locations() {
+            match location.room_id() {
+                Some(room) => floors
+                    .entry((room.block, room.floor))
+                    .or_default()
+                    .push(location.clone()),
+                None => named.push(location.clone()),
+            }
+        }
+        let mut rows: Vec<(String, Vec<Location>)> = floors
+            .into_iter()
+            .map(|((block, floor), rooms)| (format!("{}{}", block, floor), rooms))
+            .collect();
+        if !named.is_empty() {
+            rows.push((Msg::OtherRooms.to_string(), named));
+        }
+        let now = Local::now().naive_local();
+        let mut app = App {
+            campus,
+            filter,
+            ranking,
+            origins,
+            at: round_down(now),
+            rows,
+            selected: (0, 0),
+            free: Vec::new(),
+            timetable: None,
+        };
+        app.select_origin();
+        app.update();
+        app
+    }
+
+    /// Moves the selection onto the first origin room if it is in the grid.
+    fn select_origin(&mut self) {
+        let Some(origin) = self.origins.rooms.first() else {
+            return;
+        };
+        for (row, (_, rooms)) in self.rows.iter().enumerate() {
+            if let Some(col) = rooms.iter().position(|room| room.room_id() == Some(origin)) {
+                self.selected = (row, col);
+            }
+        }
+    }
+
+    fn selected_room(&self) -> Option<&Location> {
+        let (row, col) = self.selected;
+        self.rows.get(row).and_then(|(_, rooms)| rooms.get(col))
+    }
+
+    /// Ranks the free rooms for the window on the slider and rebuilds the timetable of the selected room.
+    fn update(&mut self) {
+        let end = self.at + Duration::minutes(STEP_MINUTES);
+        self.free =
+            self.campus
+                .free_rooms(&self.origins, self.at, end, &self.filter, &self.ranking);
+        self.update_timetable();
+    }
+
+    fn update_timetable(&mut self) {
+        self.timetable = self
+            .selected_room()
+            .and_then(|room| self.campus.timetable(room, self.at.date()).ok());
+    }
+
+    /// Moves the selection, keeping the column within the new row.
+    fn move_selection(&mut self, rows: isize, cols: isize) {
+        if self.rows.is_empty() {
+            return;
+        }
+        let row = (self.selected.0 as isize + rows).clamp(0, self.rows.len() as isize - 1) as usize;
+        let len = self.rows[row].1.len() as isize;
+        let col = (self.selected.1 as isize + cols).clamp(0, len - 1) as usize;
+        self.selected = (row, col);
+        self.update_timetable();
+    }
+
+    /// Handles a key press. Returns false if the UI should quit.
+    fn handle_key(&mut self, key: KeyCode) -> bool {
+        match key {
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Left | KeyCode::Char('h') => self.move_selection(0, -1),
+            KeyCode::Right | KeyCode::Char('l') => self.move_selection(0, 1),
+            KeyCode::Up | KeyCode::Char('k') => self.move_selection(-1, 0),
+            KeyCode::Down | KeyCode::Char('j') => self.move_selection(1, 0),
+            KeyCode::Char('+') | KeyCode::Char('.') => {
+                self.at += Duration::minutes(STEP_MINUTES);
+                self.update();
+            }
+            KeyCode::Char('-') | KeyCode::Char(',') => {
+                self.at -= Duration::minutes(STEP_MINUTES);
+                self.update();
+            }
+            KeyCode::Char(']') | KeyCode::PageDown => {
+                self.at += Duration::days(1);
+                self.update();
+            }
+            KeyCode::Char('[') | KeyCode::PageUp => {
+                self.at -= Duration::days(1);
+                self.update();
+            }
+            KeyCode::Char('n') => {
+                self.at = round_down(Local::now().naive_local());
+                self.update();
+            }
+            KeyCode::Char('o') => {
+                if let Some(room) = self.selected_room().and_then(|room| room.room_id()) {
+                    self.origins = Origins::single(*room);
+                    self.update();
+                }
+            }
+            _ => {}
+        }
+        true
+    }
+}
+
+/// Rounds a time down to the slider step.
+fn round_down(time: NaiveDateTime) -> NaiveDateTime {
+    let minute = time.minute() as i64 / STEP_MINUTES * STEP_MINUTES;
+    time.date()
+        .and_time(NaiveTime::from_hms_opt(time.hour(), minute as u32, 0).unwrap())
+}
+
+/// Runs the terminal UI on the local room data until the user quits.
+/// - `filter`: Rooms that are filtered out are shown but never ranked
+/// - `origins`: The rooms the free rooms are ranked from, the first one can be changed in the UI
+pub fn run(
+    filter: FilterSet,
+    ranking: Ranking,
+    origins: Origins,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let campus = Campus::load()?;
+    let mut app = App::new(campus, filter, ranking, origins);
+    let mut terminal = ratatui::init();
+    let result = event_loop(&mut terminal, &mut app);
+    ratatui::restore();
+    Ok(result?)
+}
+
+fn event_loop(terminal: &mut DefaultTerminal, app: &mut App) -> io::Result<()> {
+    loop {
+        terminal.draw(|frame| draw(frame, app))?;
+        if let Event::Key(key) = event::read()? {
+            if key.kind == KeyEventKind::Press && !app.handle_key(key.code) {
+                return Ok(());
+            }
+        }
+    }
+}
+
+fn draw(frame: &mut Frame, app: &App) {
+    let [header, slider, main, footer] = Layout::vertical([
+        Constraint::Length(1),
+        Constraint::Length(1),
+        Constraint::Min(0),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+    let [grid, side] =
+        Layout::horizontal([Constraint::Percentage(60), Constraint::Percentage(40)]).areas(main);
+    let [ranked, timetable] =
+        Layout::vertical([Constraint::Percentage(50), Constraint::Percentage(50)]).areas(side);
+
+    let origins: Vec<String> = app
+        .origins
+        .rooms
+        .iter()
+        .map(|room| room.to_string())
+        .collect();
+    frame.render_widget(
+        Paragraph::new(
+            Msg::TuiHeader(
+                &format!(
+                    "{} {}",
+                    locale::weekday(app.at.weekday()),
+                    locale::datetime(app.at)
+                ),
+                &origins.join(", "),
+                &app.free.len(),
+            )
+            .to_string(),
+        )
+        .style(Style::default().add_modifier(Modifier::BOLD)),
+        header,
+    );
+    draw_slider(frame, app, slider);
+    draw_grid(frame, app, grid);
+    draw_ranked(frame, app, ranked);
+    draw_timetable(frame, app, timetable);
+    frame.render_widget(
+        Paragraph::new(Msg::TuiKeys.to_string()).style(Style::default().fg(Color::DarkGray)),
+        footer,
+    );
+}
+
+/// Shows the time on the slider within the opening hours of the selected room's block.
+fn draw_slider(frame: &mut Frame, app: &App, area: Rect) {
+    let block = app.selected_room().and_then(|room| room.block());
+    let hours = app.campus.opening_hours.hours_on(block, app.at.date());
+    let (ratio, label) = match hours {
+        Some(hours) => {
+            let total = (hours.close - hours.open).num_minutes().max(1) as f64;
+            let passed = (app.at.time() - hours.open).num_minutes() as f64;
+            (
+                (passed / total).clamp(0.0, 1.0),
+                format!(
+                    "{}-{}",
+                    hours.open.format("%H:%M"),
+                    hours.close.format("%H:%M")
+                ),
+            )
+        }
+        None => (0.0, Msg::Closed.to_string()),
+    };
+    frame.render_widget(
+        LineGauge::default()
+            .ratio(ratio)
+            .label(label)
+            .filled_style(Style::default().fg(Color::Cyan)),
+        area,
+    );
+}
+
+/// Draws one line per block and floor with the rooms coloured by their availability.
+fn draw_grid(frame: &mut Frame, app: &App, area: Rect) {
+    let lines: Vec<Line> = app
+        .rows
+        .iter()
+        .enumerate()
+        .map(|(row, (label, rooms))| {
+            let mut spans = vec![Span::styled(
+                format!("{:>5} ", label),
+                Style::default().fg(Color::DarkGray),
+            )];
+            for (col, room) in rooms.iter().enumerate() {
+                let color = match app.campus.availability(room, app.at) {
+                    Availability::Free => Color::Green,
+                    Availability::Busy => Color::Red,
+                    Availability::Closed => Color::DarkGray,
+                };
+                let mut style = Style::default().fg(color);
+                if (row, col) == app.selected {
+                    style = style.add_modifier(Modifier::REVERSED);
+                }
+                if room
+                    .room_id()
+                    .is_some_and(|room| app.origins.rooms.contains(room))
+                {
+                    style = style.add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+                }
+                spans.push(Span::styled(room.to_string(), style));
+                spans.push(Span::raw(" "));
+            }
+            Line::from(spans)
+        })
+        .collect();
+    frame.render_widget(
+        Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(Msg::Rooms.to_string()),
+        ),
+        area,
+    );
+}
+
+/// Lists the nearest free rooms for the window on the slider.
+fn draw_ranked(frame: &mut Frame, app: &App, area: Rect) {
+    let lines: Vec<Line> = app
+        .free
+        .iter()
+        .take(area.height.saturating_sub(2) as usize)
+        .map(|room| {
+            let distance = match known_distance(room.distance) {
+                Some(distance) => distance.to_string(),
+                None => Msg::Unknown.to_string(),
+            };
+            Line::from(format!(
+                "{:<8} {:>6}  {}",
+                room.room.to_string(),
+                distance,
+                Msg::Until(&room.free_until.format("%H:%M"))
+            ))
+        })
+        .collect();
+    frame.render_widget(
+        Paragraph::new(lines).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(Msg::FreeRooms.to_string()),
+        ),
+        area,
+    );
+}
+
+/// Shows the timetable of the selected room, the entry at the slider time is highlighted.
+fn draw_timetable(frame: &mut Frame, app: &App, area: Rect) {
+    let title = match app.selected_room() {
+        Some(room) => Msg::RoomOn(room, &locale::day_month(app.at.date())).to_string(),
+        None => Msg::Timetable.to_string(),
+    };
+    let mut lines = Vec::new();
+    if let Some(day) = &app.timetable {
+        if let Some(reason) = &day.closed {
+            lines.push(Line::from(Msg::CampusClosed(reason).to_string()));
+        }
+        for entry in &day.entries {
+            let (start, end, text, color) = match entry {
+                Entry::Event(event) => {
+                    let mut text = event.title.clone();
+                    if event.status == Some(Status::Cancelled) {
+                        text.push_str(&format!(" ({})", Msg::StatusName(Status::Cancelled)));
+                    }
+                    (event.start, event.end, text, Color::Red)
+                }
+                Entry::Free { start, end } => (*start, *end, Msg::Free.to_string(), Color::Green),
+            };
+            let mut style = Style::default().fg(color);
+            if start <= app.at && app.at < end {
+                style = style.add_modifier(Modifier::REVERSED);
+            }
+            lines.push(Line::styled(
+                format!("{}-{} {}", start.format("%H:%M"), end.format("%H:%M"), text),
+                style,
+            ));
+        }
+        if day.entries.is_empty() && day.closed.is_none() {
+            lines.push(Line::from(Msg::NoEvents.to_string()));
+        }
+    }
+    frame.render_widget(
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: true })
+            .block(Block::default().borders(Borders::ALL).title(title)),
+        area,
+    );
+}
@@ -0,0 +1,68 @@
+use chrono::{Duration, NaiveDateTime};
+use std::io;
+
+use crate::free;
+use crate::holidays::Closures;
+use crate::hours::OpeningHours;
+use crate::index::RoomIndex;
+use crate::locale::Msg;
+use crate::room::Location;
+
+/// State of a watched room at one check.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WatchState {
+    /// Free for at least the requested length, until the next booking or closing time.
+    Free { until: NaiveDateTime },
+    /// Free now, but booked or closed again before the requested length is over.
+    Short { until: NaiveDateTime },
+    /// Booked now, until the end of the current (and directly following) bookings.
+    Busy { until: NaiveDateTime },
+    /// The campus or the block of the room is closed, with the reason.
+    Closed(String),
+}
+
+/// Checks whether a room is free from `at` for at least `length`.
+/// Reads the local calendar files on every call, so calendars downloaded by a refresh
+/// in the meantime are used, but never downloads anything itself.
+pub fn check(
+    location: &Location,
+    at: NaiveDateTime,
+    length: Duration,
+) -> Result<WatchState, Box<dyn std::error::Error>> {
+    let index =
+        RoomIndex::load().map_err(|e| io::Error::new(e.kind(), Msg::NoRoomData(&e).to_string()))?;
+    if !index.rooms.contains_key(location) {
+        return Err(Msg::UnknownRoom(location).to_string().into());
+    }
+    if let Some(reason) = Closures::load()?.closed_reason(at.date()) {
+        return Ok(WatchState::Closed(reason));
+    }
+    let hours = OpeningHours::load()?.hours_on(location.block(), at.date());
+    let Some(hours) = hours.filter(|hours| hours.open <= at.time() && at.time() < hours.close)
+    else {
+        return Ok(WatchState::Closed(Msg::OutsideOpeningHours.to_string()));
+    };
+
+    let bookings = index.bookings(location)?;
+    // Follow bookings that start before the previous one ends
+    let mut busy_until = at;
+    while let Some(end) = bookings
+        .iter()
+        .filter(|booking| booking.start <= busy_until && busy_until < booking.end)
+        .map(|booking| booking.end)
+        .max()
+    {
+        busy_until = end;
+    }
+    if busy_until > at {
+        return Ok(WatchState::Busy { until: busy_until });
+    }
+
+    let closing = at.date().and_time(hours.close);
+    let until = free::next_booking(&bookings, at).map_or(closing, |next| next.min(closing));
+    if until - at >= length {
+        Ok(WatchState::Free { until })
+    } else {
+        Ok(WatchState::Short { until })
+    }
+}